@@ -209,11 +209,14 @@ async fn main() -> anyhow::Result<()> {
         if let Some(ref mut client) = mqtt_client_guard.as_mut() {
             client.connect().await.unwrap();
             
-            if let Some(client_ref) = client.client.as_ref() {
-                client_ref.subscribe("/ai-core/from-user/message", QoS::AtLeastOnce).await?;
-                client_ref.subscribe("/ai-core/from-module/message", QoS::AtLeastOnce).await?;
-                log::info!("✅ MQTT 订阅已设置");
+            // 通过 MqttClient 订阅，断线重连后会自动重新订阅
+            for topic in ["/ai-core/from-user/message", "/ai-core/from-module/message"] {
+                client
+                    .subscribe(topic, QoS::AtLeastOnce)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
             }
+            log::info!("✅ MQTT 订阅已设置");
         }
     }
 
@@ -1,24 +1,35 @@
-use ollama_models::{OllamaRequest, OllamaResponse};
+use ollama_models::{
+    BudgetStatus, OllamaRequest, OllamaResponse, StreamAggregator, StreamDecoder, TokenBudget,
+    DEFAULT_NUM_CTX,
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Ollama 客户端
 /// 
-/// 用于与 Ollama API 交互，支持会话上下文管理
+/// 用于与 Ollama API 交互，支持会话上下文管理；上下文接近模型窗口上限时会自动开始新会话
 #[derive(Clone)]
 pub struct OllamaClient {
     base_url: String,
     context: Arc<RwLock<Option<Vec<i64>>>>,
+    /// 当前会话的模型与 token 预算
+    budget: Arc<RwLock<Option<(String, TokenBudget)>>>,
     client: reqwest::Client,
+    /// 模型管理接口（`/api/show` 等）
+    api: ollama_models::OllamaClient,
 }
 
 impl OllamaClient {
     /// 创建新的 Ollama 客户端
     pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        let client = reqwest::Client::new();
         Self {
-            base_url: base_url.into(),
+            api: ollama_models::OllamaClient::new(base_url.clone()).with_http_client(client.clone()),
+            base_url,
             context: Arc::new(RwLock::new(None)),
-            client: reqwest::Client::new(),
+            budget: Arc::new(RwLock::new(None)),
+            client,
         }
     }
 
@@ -45,22 +56,36 @@ impl OllamaClient {
             log::info!("🔄 清空 Ollama 会话上下文");
         }
 
+        let prompt_str = prompt.into();
+        let model_str = model.into();
+
+        // 检查上下文预算：generate 的 context 无法部分裁剪，超过阈值时开始新会话
+        let (mut budget, resolved) = self.budget_for(&model_str).await;
+        if new_session {
+            budget.reset();
+        }
+        match budget.check(&prompt_str) {
+            BudgetStatus::Ok => {}
+            BudgetStatus::Summarize => {
+                log::info!("📏 会话上下文已使用 {:.0}%，剩余 {} tokens", budget.usage_ratio() * 100.0, budget.remaining());
+            }
+            BudgetStatus::Trim => {
+                log::warn!("✂️ 会话上下文将超过窗口 ({} tokens)，清空上下文", budget.num_ctx());
+                self.clear_context().await;
+                budget.reset();
+            }
+        }
+
         // 读取当前 context
         let current_context = {
             let context_guard = self.context.read().await;
             context_guard.clone()
         };
 
-        let prompt_str = prompt.into();
-        let model_str = model.into();
-
         // 构造请求
-        let request = OllamaRequest {
-            model: model_str.clone(),
-            prompt: prompt_str.clone(),
-            stream: false,
-            context: current_context,
-        };
+        let request = OllamaRequest::new(model_str.clone(), prompt_str.clone())
+            .with_stream(true)
+            .with_context(current_context);
 
         log::info!("🤖 向 Ollama 发送请求: 模型={}, 提示词长度={}", model_str, prompt_str.len());
 
@@ -72,14 +97,47 @@ impl OllamaClient {
             let mut context_guard = self.context.write().await;
             *context_guard = Some(new_context.clone());
         }
+        budget.record_response(&response);
+        log::debug!("📏 上下文已使用 {} / {} tokens", budget.used(), budget.available());
+        *self.budget.write().await = resolved.then_some((model_str, budget));
 
         log::info!("✅ Ollama 响应: 长度={}, 完成={}", response.response.len(), response.done);
 
         Ok(response.response)
     }
 
-    /// 发送请求到 Ollama API
+    /// 当前会话的 token 预算，切换模型时根据 `/api/show` 重新确定上下文窗口
+    ///
+    /// 第二项表示上下文窗口是否来自 `/api/show`；获取失败时使用默认值，但不缓存，下次请求重新获取
+    async fn budget_for(&self, model: &str) -> (TokenBudget, bool) {
+        if let Some((current, budget)) = self.budget.read().await.as_ref() {
+            if current == model {
+                return (budget.clone(), true);
+            }
+        }
+        let (mut budget, resolved) = match self.api.token_budget(model, None).await {
+            Ok(budget) => (budget, true),
+            Err(e) => {
+                log::warn!("⚠️ 获取模型 {} 的上下文长度失败，暂时使用默认值 {}: {}", model, DEFAULT_NUM_CTX, e);
+                (TokenBudget::new(DEFAULT_NUM_CTX), false)
+            }
+        };
+        if let Some(context) = self.get_context().await {
+            budget.set_estimated(u32::try_from(context.len()).unwrap_or(u32::MAX));
+        }
+        (budget, resolved)
+    }
+
+    /// 发送请求到 Ollama API（`stream` 为 true 时按流式读取并合并）
     pub async fn send_request(&self, request: OllamaRequest) -> Result<OllamaResponse, Box<dyn std::error::Error + Send + Sync>> {
+        if request.stream {
+            return self
+                .send_request_stream(request, |chunk| {
+                    log::debug!("📨 Ollama 流式响应块: {:?}", chunk.response);
+                })
+                .await;
+        }
+
         let url = format!("{}/api/generate", self.base_url);
         
         let response = self.client
@@ -97,6 +155,46 @@ impl OllamaClient {
         Ok(ollama_response)
     }
 
+    /// 以流式方式发送请求，每收到一块调用一次 `on_chunk`，返回合并后的完整响应
+    pub async fn send_request_stream(
+        &self,
+        mut request: OllamaRequest,
+        mut on_chunk: impl FnMut(&OllamaResponse),
+    ) -> Result<OllamaResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/api/generate", self.base_url);
+        request.stream = true;
+
+        let mut response = self.client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP error: {}", response.status()).into());
+        }
+
+        let mut decoder = StreamDecoder::<OllamaResponse>::new();
+        let mut aggregator = StreamAggregator::new();
+        while let Some(bytes) = response.chunk().await? {
+            for chunk in decoder.feed(&bytes)? {
+                on_chunk(&chunk);
+                aggregator.push(chunk);
+            }
+        }
+        if let Some(chunk) = decoder.finish()? {
+            on_chunk(&chunk);
+            aggregator.push(chunk);
+        }
+
+        if let Some(stats) = aggregator.performance_stats() {
+            log::info!("📊 {}", stats.format_summary());
+        }
+        aggregator
+            .finish()
+            .ok_or_else(|| "Empty stream response".into())
+    }
+
     /// 获取当前会话上下文
     pub async fn get_context(&self) -> Option<Vec<i64>> {
         let context = self.context.read().await;
@@ -107,6 +205,9 @@ impl OllamaClient {
     pub async fn clear_context(&self) {
         let mut context = self.context.write().await;
         *context = None;
+        if let Some((_, budget)) = self.budget.write().await.as_mut() {
+            budget.reset();
+        }
     }
 
     /// 设置会话上下文
@@ -142,4 +243,13 @@ mod tests {
         let cleared_context = client.get_context().await;
         assert_eq!(cleared_context, None);
     }
+
+    #[tokio::test]
+    async fn test_fallback_budget_not_cached() {
+        // 端口不可用，`/api/show` 失败时回退到默认上下文窗口
+        let client = OllamaClient::new("http://127.0.0.1:9");
+        let (budget, resolved) = client.budget_for("llama3.2").await;
+        assert!(!resolved);
+        assert_eq!(budget.num_ctx(), DEFAULT_NUM_CTX);
+    }
 }
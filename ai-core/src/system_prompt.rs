@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use ollama_models::OllamaResponse;
+use ollama_models::{OllamaRequest, OllamaResponse};
 
 /// 系统参数设定请求
 #[derive(Debug, Clone, Deserialize)]
@@ -25,17 +25,6 @@ pub struct SetSystemPromptResponse {
     pub session_id: Option<String>,
 }
 
-/// Ollama 请求体
-#[derive(Debug, Clone, Serialize)]
-struct OllamaSystemRequest {
-    model: String,
-    prompt: String,
-    system: String,
-    stream: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    context: Option<Vec<i64>>,
-}
-
 /// Ollama 响应体（使用 ollama_client 模块中的定义）
 
 /// 会话存储结构
@@ -109,13 +98,9 @@ pub async fn set_system_prompt(
     let model = std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| "gpt-oss:20b".to_string());
 
     // 构造 Ollama 请求
-    let ollama_request = OllamaSystemRequest {
-        model,
-        prompt: prompt_preview.to_string(), // 简单的确认消息
-        system: request.system_prompt.clone(),
-        stream: false,
-        context,
-    };
+    let ollama_request = OllamaRequest::new(model, prompt_preview) // 简单的确认消息
+        .with_system(request.system_prompt.clone())
+        .with_context(context);
     log::info!("Ollama 请求: {:?}", ollama_request);
     // 发送 HTTP 请求到 Ollama
     let client = reqwest::Client::new();
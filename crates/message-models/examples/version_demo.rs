@@ -63,24 +63,36 @@ fn main() {
     }
 
     // 4. 版本兼容性检查
-    println!("🔄 版本兼容性:");
-    let v0 = SchemaVersion::V0;
-    println!("  v0 与 v0 兼容: {}", v0.is_compatible_with(&SchemaVersion::V0));
+    println!("🔄 版本兼容性（读取方 -> 消息版本）:");
+    for reader in SchemaVersion::all() {
+        for message in SchemaVersion::all() {
+            println!(
+                "  {} 读取 {}: {}",
+                reader,
+                message,
+                reader.is_compatible_with(&message)
+            );
+        }
+    }
     println!();
 
-    // 5. 转换和升级（未来功能预览）
-    println!("🚀 版本转换预览:");
+    // 5. 版本转换
+    println!("🚀 版本转换:");
     let json = r#"{ "type": "user", "content": "测试" }"#;
     let versioned = VersionedEnvelope::from_json(json).unwrap();
-    
-    match versioned.upgrade_to(SchemaVersion::V0) {
-        Ok(_) => println!("  ✅ 保持在 v0 版本"),
-        Err(e) => println!("  ❌ 转换失败: {}", e),
+
+    match versioned.upgrade_to(SchemaVersion::V1) {
+        Ok(upgraded) => {
+            println!("  ✅ v0 -> v1: {}", upgraded.to_json().unwrap());
+
+            match upgraded.upgrade_to(SchemaVersion::V0) {
+                Ok(downgraded) => println!("  ✅ v1 -> v0: {}", downgraded.to_json().unwrap()),
+                Err(e) => println!("  ❌ 降级失败: {}", e),
+            }
+        }
+        Err(e) => println!("  ❌ 升级失败: {}", e),
     }
-    
-    // 尝试"升级"到不存在的版本（演示错误处理）
-    println!("  尝试升级到未来版本（应该失败）...");
-    println!("  （未来添加 v1 时，这里会展示实际的版本转换）\n");
+    println!();
 
     // 6. 实用工具
     println!("🛠️  实用工具:");
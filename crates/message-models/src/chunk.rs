@@ -0,0 +1,651 @@
+//! 分块传输
+//!
+//! broker 限制了单条消息的大小（`rumqttd.toml` 中 `max_payload_size = 20480`），
+//! 较大的负载（系统提示词、对话记录、模块结果等）需要拆成多个 [`Chunk`] 发送，
+//! 接收端用 [`Reassembler`] 按 `transfer_id` 重组，并用 SHA-256 校验完整性。
+//! 超过时限仍未收齐的传输会被丢弃；同时重组的传输数量与缓存的字节数都有上限，
+//! 伪造的块不会让接收端分配过多内存。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+/// 分块消息的 content-type
+pub const CHUNK_CONTENT_TYPE: &str = "application/vnd.cozymind.chunk+json";
+
+/// 默认每块的原始数据大小：base64 编码并加上元数据后仍小于 20 KB
+pub const DEFAULT_CHUNK_SIZE: usize = 12 * 1024;
+
+/// 默认的重组时限
+pub const DEFAULT_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 默认允许重组的最大负载（16 MB）
+pub const DEFAULT_MAX_TRANSFER_SIZE: u64 = 16 * 1024 * 1024;
+
+/// 默认允许同时重组的传输数量
+pub const DEFAULT_MAX_PENDING_TRANSFERS: usize = 64;
+
+/// 默认允许缓存的块数据总量（64 MB）
+pub const DEFAULT_MAX_BUFFERED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 计算负载的 SHA-256（十六进制）
+pub fn content_hash(payload: &[u8]) -> String {
+    hex::encode(Sha256::digest(payload))
+}
+
+/// 一个数据块
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct Chunk {
+    /// 传输 ID，同一负载的所有块共享
+    pub transfer_id: String,
+    /// 块序号（从 0 开始）
+    pub index: u32,
+    /// 块总数
+    pub count: u32,
+    /// 每块的字节数（最后一块可以更短）
+    pub chunk_size: u32,
+    /// 完整负载的字节数
+    #[ts(type = "number")]
+    pub total_size: u64,
+    /// 完整负载的 SHA-256（十六进制）
+    pub hash: String,
+    /// 完整负载的 content-type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub content_type: Option<String>,
+    /// 本块数据（base64）
+    #[serde(with = "base64_data")]
+    #[ts(type = "string")]
+    pub data: Vec<u8>,
+}
+
+mod base64_data {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Chunk {
+    /// 将负载拆分为多个块，每块最多 `chunk_size` 字节
+    ///
+    /// 空负载也会生成一个（空的）块
+    pub fn split(payload: &[u8], chunk_size: usize, content_type: Option<&str>) -> Vec<Chunk> {
+        let chunk_size = chunk_size.clamp(1, u32::MAX as usize);
+        let transfer_id = uuid::Uuid::new_v4().to_string();
+        let hash = content_hash(payload);
+
+        let pieces: Vec<&[u8]> = if payload.is_empty() {
+            vec![&[]]
+        } else {
+            payload.chunks(chunk_size).collect()
+        };
+        let count = pieces.len() as u32;
+
+        pieces
+            .into_iter()
+            .enumerate()
+            .map(|(index, data)| Chunk {
+                transfer_id: transfer_id.clone(),
+                index: index as u32,
+                count,
+                chunk_size: chunk_size as u32,
+                total_size: payload.len() as u64,
+                hash: hash.clone(),
+                content_type: content_type.map(str::to_string),
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    /// 单条消息不超过 `max_message_size` 字节时，每块最多能携带的数据大小
+    ///
+    /// 扣除 JSON 元数据（按最长的序号与块数计算）与 base64 编码的开销；
+    /// 上限小到放不下元数据时返回 `None`
+    pub fn max_data_size(
+        max_message_size: usize,
+        total_size: u64,
+        content_type: Option<&str>,
+    ) -> Option<usize> {
+        let template = Chunk {
+            transfer_id: uuid::Uuid::nil().to_string(),
+            index: u32::MAX,
+            count: u32::MAX,
+            chunk_size: u32::MAX,
+            total_size,
+            hash: content_hash(&[]),
+            content_type: content_type.map(str::to_string),
+            data: Vec::new(),
+        };
+        let overhead = template.to_vec().ok()?.len();
+        // base64 每 4 个字符编码 3 个字节
+        let size = max_message_size.checked_sub(overhead)? / 4 * 3;
+        (size > 0).then_some(size)
+    }
+
+    /// 验证块自身的一致性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.transfer_id.is_empty() {
+            return Err("Chunk 'transfer_id' is empty".to_string());
+        }
+        if self.chunk_size == 0 {
+            return Err("Chunk 'chunk_size' must be at least 1".to_string());
+        }
+        // 块数量必须与负载大小一致，避免按伪造的 count 分配内存
+        let expected = self.total_size.div_ceil(u64::from(self.chunk_size)).max(1);
+        if u64::from(self.count) != expected {
+            return Err(format!(
+                "Chunk count {} does not match 'total_size' {} with 'chunk_size' {}",
+                self.count, self.total_size, self.chunk_size
+            ));
+        }
+        if self.index >= self.count {
+            return Err(format!(
+                "Chunk index {} is out of range for count {}",
+                self.index, self.count
+            ));
+        }
+        let offset = u64::from(self.index) * u64::from(self.chunk_size);
+        let expected_len = (self.total_size - offset).min(u64::from(self.chunk_size));
+        if self.data.len() as u64 != expected_len {
+            return Err(format!(
+                "Chunk {} has {} bytes, expected {}",
+                self.index,
+                self.data.len(),
+                expected_len
+            ));
+        }
+        Ok(())
+    }
+
+    /// 从JSON字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 从JSON字节解析
+    pub fn from_slice(payload: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(payload)
+    }
+
+    /// 转换为JSON字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// 转换为JSON字节
+    pub fn to_vec(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+}
+
+/// 重组完成的负载
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assembled {
+    pub transfer_id: String,
+    pub content_type: Option<String>,
+    pub payload: Vec<u8>,
+}
+
+/// 分块重组错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkError {
+    /// 块本身不合法
+    Invalid(String),
+    /// 块的元数据与同一传输中已收到的块不一致
+    Inconsistent { transfer_id: String, reason: String },
+    /// 重组后的负载哈希不匹配
+    HashMismatch { transfer_id: String },
+    /// 超过同时重组的传输数量或缓存大小上限
+    LimitExceeded(String),
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(reason) => write!(f, "Invalid chunk: {}", reason),
+            Self::Inconsistent {
+                transfer_id,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "Inconsistent chunk for transfer '{}': {}",
+                    transfer_id, reason
+                )
+            }
+            Self::HashMismatch { transfer_id } => {
+                write!(f, "Hash mismatch for transfer '{}'", transfer_id)
+            }
+            Self::LimitExceeded(reason) => write!(f, "Reassembly limit exceeded: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ChunkError {}
+
+/// 重组中的传输
+struct Partial {
+    count: u32,
+    chunk_size: u32,
+    total_size: u64,
+    hash: String,
+    content_type: Option<String>,
+    /// 已收到的块，按序号排列
+    parts: BTreeMap<u32, Vec<u8>>,
+    started_at: Instant,
+}
+
+impl Partial {
+    fn new(chunk: &Chunk, now: Instant) -> Self {
+        Self {
+            count: chunk.count,
+            chunk_size: chunk.chunk_size,
+            total_size: chunk.total_size,
+            hash: chunk.hash.clone(),
+            content_type: chunk.content_type.clone(),
+            parts: BTreeMap::new(),
+            started_at: now,
+        }
+    }
+
+    fn check(&self, chunk: &Chunk) -> Result<(), String> {
+        if chunk.count != self.count {
+            return Err(format!("count {} != {}", chunk.count, self.count));
+        }
+        if chunk.chunk_size != self.chunk_size {
+            return Err(format!(
+                "chunk_size {} != {}",
+                chunk.chunk_size, self.chunk_size
+            ));
+        }
+        if chunk.total_size != self.total_size {
+            return Err(format!(
+                "total_size {} != {}",
+                chunk.total_size, self.total_size
+            ));
+        }
+        if chunk.hash != self.hash {
+            return Err("hash differs from earlier chunks".to_string());
+        }
+        if chunk.content_type != self.content_type {
+            return Err(format!(
+                "content_type {:?} != {:?}",
+                chunk.content_type, self.content_type
+            ));
+        }
+        Ok(())
+    }
+
+    /// 已缓存的字节数
+    fn buffered(&self) -> u64 {
+        self.parts.values().map(|data| data.len() as u64).sum()
+    }
+}
+
+/// 分块重组器
+///
+/// 按 `transfer_id` 收集块，收齐后校验哈希并返回完整负载；重复的块会被忽略
+pub struct Reassembler {
+    timeout: Duration,
+    max_size: u64,
+    max_pending: usize,
+    max_buffered: u64,
+    /// 所有未完成传输已缓存的字节数
+    buffered: u64,
+    pending: HashMap<String, Partial>,
+}
+
+impl Default for Reassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_REASSEMBLY_TIMEOUT)
+    }
+}
+
+impl Reassembler {
+    /// 使用指定的重组时限创建
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            max_size: DEFAULT_MAX_TRANSFER_SIZE,
+            max_pending: DEFAULT_MAX_PENDING_TRANSFERS,
+            max_buffered: DEFAULT_MAX_BUFFERED_SIZE,
+            buffered: 0,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// 设置允许重组的最大负载
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// 设置允许同时重组的传输数量
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending;
+        self
+    }
+
+    /// 设置允许缓存的块数据总量
+    pub fn with_max_buffered(mut self, max_buffered: u64) -> Self {
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// 重组时限
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// 正在重组的传输数量
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// 未完成传输已缓存的字节数
+    pub fn buffered(&self) -> u64 {
+        self.buffered
+    }
+
+    /// 接收一个块；收齐时返回完整负载
+    pub fn push(&mut self, chunk: Chunk) -> Result<Option<Assembled>, ChunkError> {
+        self.push_at(chunk, Instant::now())
+    }
+
+    /// 接收一个块（指定当前时间，便于测试）
+    pub fn push_at(&mut self, chunk: Chunk, now: Instant) -> Result<Option<Assembled>, ChunkError> {
+        chunk.validate().map_err(ChunkError::Invalid)?;
+        if chunk.total_size > self.max_size {
+            return Err(ChunkError::Invalid(format!(
+                "Transfer size {} exceeds limit {}",
+                chunk.total_size, self.max_size
+            )));
+        }
+
+        if !self.pending.contains_key(&chunk.transfer_id) && self.pending.len() >= self.max_pending
+        {
+            return Err(ChunkError::LimitExceeded(format!(
+                "{} transfers are already pending",
+                self.pending.len()
+            )));
+        }
+
+        let partial = self
+            .pending
+            .entry(chunk.transfer_id.clone())
+            .or_insert_with(|| Partial::new(&chunk, now));
+
+        if let Err(reason) = partial.check(&chunk) {
+            return Err(ChunkError::Inconsistent {
+                transfer_id: chunk.transfer_id,
+                reason,
+            });
+        }
+
+        if !partial.parts.contains_key(&chunk.index) {
+            let len = chunk.data.len() as u64;
+            if self.buffered + len > self.max_buffered {
+                if partial.parts.is_empty() {
+                    self.pending.remove(&chunk.transfer_id);
+                }
+                return Err(ChunkError::LimitExceeded(format!(
+                    "buffering {} more bytes would exceed {}",
+                    len, self.max_buffered
+                )));
+            }
+            partial.parts.insert(chunk.index, chunk.data);
+            self.buffered += len;
+        }
+
+        if (partial.parts.len() as u64) < u64::from(partial.count) {
+            return Ok(None);
+        }
+
+        let partial = self
+            .pending
+            .remove(&chunk.transfer_id)
+            .expect("partial transfer exists");
+        self.buffered -= partial.buffered();
+        let payload: Vec<u8> = partial.parts.into_values().flatten().collect();
+
+        if payload.len() as u64 != partial.total_size || content_hash(&payload) != partial.hash {
+            return Err(ChunkError::HashMismatch {
+                transfer_id: chunk.transfer_id,
+            });
+        }
+
+        Ok(Some(Assembled {
+            transfer_id: chunk.transfer_id,
+            content_type: partial.content_type,
+            payload,
+        }))
+    }
+
+    /// 丢弃超时未收齐的传输，返回其 transfer_id
+    pub fn expire(&mut self) -> Vec<String> {
+        self.expire_at(Instant::now())
+    }
+
+    /// 丢弃超时未收齐的传输（指定当前时间，便于测试）
+    pub fn expire_at(&mut self, now: Instant) -> Vec<String> {
+        let timeout = self.timeout;
+        let expired: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, partial)| now.saturating_duration_since(partial.started_at) >= timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &expired {
+            if let Some(partial) = self.pending.remove(id) {
+                self.buffered -= partial.buffered();
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_split_and_reassemble_out_of_order() {
+        let data = payload(50_000);
+        let mut chunks = Chunk::split(&data, DEFAULT_CHUNK_SIZE, Some("application/json"));
+        assert_eq!(chunks.len(), 5);
+        chunks.reverse();
+
+        let mut reassembler = Reassembler::default();
+        let last = chunks.pop().unwrap();
+        for chunk in chunks {
+            assert_eq!(reassembler.push(chunk).unwrap(), None);
+        }
+        let assembled = reassembler.push(last).unwrap().unwrap();
+
+        assert_eq!(assembled.payload, data);
+        assert_eq!(assembled.content_type.as_deref(), Some("application/json"));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_chunk_fits_broker_limit() {
+        let chunks = Chunk::split(
+            &payload(DEFAULT_CHUNK_SIZE),
+            DEFAULT_CHUNK_SIZE,
+            Some(CHUNK_CONTENT_TYPE),
+        );
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].to_vec().unwrap().len() < 20480);
+
+        let json = chunks[0].to_json().unwrap();
+        assert_eq!(Chunk::from_json(&json).unwrap(), chunks[0]);
+    }
+
+    #[test]
+    fn test_max_data_size() {
+        let data = payload(50_000);
+        for max in [512, 1024, 20480] {
+            let size =
+                Chunk::max_data_size(max, data.len() as u64, Some("application/json")).unwrap();
+            let chunks = Chunk::split(&data, size, Some("application/json"));
+            assert!(chunks.iter().all(|c| c.to_vec().unwrap().len() <= max));
+        }
+        assert_eq!(Chunk::max_data_size(100, 50_000, None), None);
+    }
+
+    #[test]
+    fn test_duplicate_chunks_are_ignored() {
+        let data = payload(100);
+        let chunks = Chunk::split(&data, 40, None);
+        let mut reassembler = Reassembler::default();
+
+        assert_eq!(reassembler.push(chunks[0].clone()).unwrap(), None);
+        assert_eq!(reassembler.push(chunks[0].clone()).unwrap(), None);
+        assert_eq!(reassembler.push(chunks[1].clone()).unwrap(), None);
+        assert!(reassembler.push(chunks[2].clone()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_hash_mismatch_and_invalid_chunks() {
+        let mut chunks = Chunk::split(&payload(10), 4, None);
+        chunks[1].data[0] ^= 0xff;
+
+        let mut reassembler = Reassembler::default();
+        let mut result = Ok(None);
+        for chunk in chunks.clone() {
+            result = reassembler.push(chunk);
+        }
+        assert!(matches!(result, Err(ChunkError::HashMismatch { .. })));
+
+        let mut out_of_range = chunks[0].clone();
+        out_of_range.index = out_of_range.count;
+        assert!(matches!(
+            reassembler.push(out_of_range),
+            Err(ChunkError::Invalid(_))
+        ));
+
+        // 块数量与负载大小不符，避免按伪造的 count 分配内存
+        let mut oversized = chunks[0].clone();
+        oversized.count = u32::MAX;
+        assert!(matches!(
+            reassembler.push(oversized),
+            Err(ChunkError::Invalid(_))
+        ));
+
+        let mut limited = Reassembler::default().with_max_size(5);
+        assert!(matches!(
+            limited.push(chunks[0].clone()),
+            Err(ChunkError::Invalid(_))
+        ));
+
+        // 同一传输中途改变 content-type
+        let mut inconsistent = chunks[1].clone();
+        reassembler.push(chunks[0].clone()).unwrap();
+        inconsistent.content_type = Some("text/plain".to_string());
+        assert!(matches!(
+            reassembler.push(inconsistent),
+            Err(ChunkError::Inconsistent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_count_must_match_size() {
+        let chunks = Chunk::split(&payload(10), 4, None);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| chunk.validate().is_ok()));
+
+        // count 与 total_size / chunk_size 不符
+        let mut forged = chunks[0].clone();
+        forged.count = 10;
+        assert!(forged.validate().is_err());
+        let mut forged = chunks[0].clone();
+        forged.chunk_size = 1;
+        assert!(forged.validate().is_err());
+
+        // 数据长度与序号不符：只有最后一块可以更短
+        let mut short = chunks[0].clone();
+        short.data.pop();
+        assert!(short.validate().is_err());
+        let mut long = chunks[2].clone();
+        long.data.push(0);
+        assert!(long.validate().is_err());
+    }
+
+    #[test]
+    fn test_reassembly_limits() {
+        let first = Chunk::split(&payload(100), 40, None);
+        let second = Chunk::split(&payload(100), 40, None);
+
+        let mut reassembler = Reassembler::default().with_max_pending(1);
+        reassembler.push(first[0].clone()).unwrap();
+        assert!(matches!(
+            reassembler.push(second[0].clone()),
+            Err(ChunkError::LimitExceeded(_))
+        ));
+        // 已在重组的传输不受影响
+        reassembler.push(first[1].clone()).unwrap();
+        assert!(reassembler.push(first[2].clone()).unwrap().is_some());
+        assert!(reassembler.push(second[0].clone()).unwrap().is_none());
+
+        let mut reassembler = Reassembler::default().with_max_buffered(100);
+        reassembler.push(first[0].clone()).unwrap();
+        reassembler.push(first[1].clone()).unwrap();
+        assert_eq!(reassembler.buffered(), 80);
+        assert!(matches!(
+            reassembler.push(second[0].clone()),
+            Err(ChunkError::LimitExceeded(_))
+        ));
+        assert_eq!(reassembler.pending(), 1);
+
+        // 收齐或超时后释放缓存
+        assert!(reassembler.push(first[2].clone()).unwrap().is_some());
+        assert_eq!(reassembler.buffered(), 0);
+        reassembler.push(second[0].clone()).unwrap();
+        reassembler.expire_at(Instant::now() + DEFAULT_REASSEMBLY_TIMEOUT);
+        assert_eq!(reassembler.buffered(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_transfer_expires() {
+        let chunks = Chunk::split(&payload(100), 40, None);
+        let mut reassembler = Reassembler::new(Duration::from_secs(5));
+        let start = Instant::now();
+
+        reassembler.push_at(chunks[0].clone(), start).unwrap();
+        assert!(reassembler
+            .expire_at(start + Duration::from_secs(1))
+            .is_empty());
+
+        let expired = reassembler.expire_at(start + Duration::from_secs(5));
+        assert_eq!(expired, vec![chunks[0].transfer_id.clone()]);
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_empty_payload() {
+        let chunks = Chunk::split(&[], DEFAULT_CHUNK_SIZE, None);
+        assert_eq!(chunks.len(), 1);
+        let assembled = Reassembler::default()
+            .push(chunks[0].clone())
+            .unwrap()
+            .unwrap();
+        assert!(assembled.payload.is_empty());
+    }
+}
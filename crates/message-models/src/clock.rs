@@ -0,0 +1,272 @@
+//! 时间来源
+//!
+//! `MessageMeta` 的默认时间戳来自当前的 [`TimeSource`]（时钟 + 时区）：
+//! - 进程级默认值：[`set_default`] / [`set_default_timezone`]，默认为系统时钟 + 东八区
+//! - 线程内临时替换：[`with_time_source`]，测试中用于生成确定的时间戳
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// 时钟
+pub trait Clock: Send + Sync {
+    /// 当前 UTC 时间
+    fn now_utc(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 固定时钟，总是返回同一时间
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// 时间戳使用的时区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timezone {
+    /// UTC
+    Utc,
+    /// 固定偏移
+    Fixed(FixedOffset),
+    /// 系统本地时区
+    Local,
+}
+
+impl Timezone {
+    /// 东八区（UTC+8）
+    pub fn china() -> Self {
+        Self::Fixed(FixedOffset::east_opt(8 * 3600).unwrap())
+    }
+
+    /// 将 UTC 时间转换到本时区
+    pub fn convert(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let offset = match self {
+            Self::Utc => Utc.fix(),
+            Self::Fixed(offset) => *offset,
+            Self::Local => *utc.with_timezone(&Local).offset(),
+        };
+        utc.with_timezone(&offset)
+    }
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::china()
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utc => write!(f, "utc"),
+            Self::Fixed(offset) => write!(f, "{}", offset),
+            Self::Local => write!(f, "local"),
+        }
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    /// 支持 `utc`、`local` 以及 `+08:00` / `-05:30` 形式的固定偏移
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "utc" | "z" => Ok(Self::Utc),
+            "local" => Ok(Self::Local),
+            other => other
+                .parse::<FixedOffset>()
+                .map(Self::Fixed)
+                .map_err(|_| format!("Unknown timezone: {}", s)),
+        }
+    }
+}
+
+/// 时间来源：时钟 + 时区
+#[derive(Clone)]
+pub struct TimeSource {
+    clock: Arc<dyn Clock>,
+    timezone: Timezone,
+}
+
+impl TimeSource {
+    /// 使用指定时钟和时区创建
+    pub fn new(clock: impl Clock + 'static, timezone: Timezone) -> Self {
+        Self {
+            clock: Arc::new(clock),
+            timezone,
+        }
+    }
+
+    /// 系统时钟 + 指定时区
+    pub fn system(timezone: Timezone) -> Self {
+        Self::new(SystemClock, timezone)
+    }
+
+    /// 固定时间 + 指定时区
+    pub fn fixed(at: DateTime<Utc>, timezone: Timezone) -> Self {
+        Self::new(FixedClock(at), timezone)
+    }
+
+    /// 替换时区
+    pub fn with_timezone(mut self, timezone: Timezone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// 时区
+    pub fn timezone(&self) -> Timezone {
+        self.timezone
+    }
+
+    /// 当前时间（已转换到本时区）
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.timezone.convert(self.clock.now_utc())
+    }
+}
+
+impl Default for TimeSource {
+    fn default() -> Self {
+        Self::system(Timezone::default())
+    }
+}
+
+impl fmt::Debug for TimeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimeSource")
+            .field("timezone", &self.timezone)
+            .finish_non_exhaustive()
+    }
+}
+
+fn global() -> &'static RwLock<TimeSource> {
+    static DEFAULT: OnceLock<RwLock<TimeSource>> = OnceLock::new();
+    DEFAULT.get_or_init(|| RwLock::new(TimeSource::default()))
+}
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<TimeSource>> = const { RefCell::new(None) };
+}
+
+/// 设置进程级默认时间来源
+pub fn set_default(source: TimeSource) {
+    *global().write().unwrap_or_else(|e| e.into_inner()) = source;
+}
+
+/// 只修改进程级默认时区，保留当前时钟
+pub fn set_default_timezone(timezone: Timezone) {
+    global().write().unwrap_or_else(|e| e.into_inner()).timezone = timezone;
+}
+
+/// 当前生效的时间来源（线程内替换优先于进程级默认值）
+pub fn current() -> TimeSource {
+    OVERRIDE
+        .with(|o| o.borrow().clone())
+        .unwrap_or_else(|| global().read().unwrap_or_else(|e| e.into_inner()).clone())
+}
+
+/// 当前时间（使用当前生效的时间来源）
+pub fn now() -> DateTime<FixedOffset> {
+    current().now()
+}
+
+/// 在当前线程内临时使用指定的时间来源执行 `f`
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use message_models::clock::{self, TimeSource, Timezone};
+/// use message_models::Envelope;
+///
+/// let at = Utc.with_ymd_and_hms(2024, 10, 11, 8, 0, 0).unwrap();
+/// let msg = clock::with_time_source(TimeSource::fixed(at, Timezone::Utc), || Envelope::user("测试"));
+/// assert_eq!(msg.meta.timestamp.to_rfc3339(), "2024-10-11T08:00:00+00:00");
+/// ```
+pub fn with_time_source<R>(source: TimeSource, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<TimeSource>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            OVERRIDE.with(|o| *o.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(OVERRIDE.with(|o| o.borrow_mut().replace(source)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 10, 11, 8, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_timezone_convert() {
+        assert_eq!(
+            Timezone::Utc.convert(at()).to_rfc3339(),
+            "2024-10-11T08:00:00+00:00"
+        );
+        assert_eq!(
+            Timezone::china().convert(at()).to_rfc3339(),
+            "2024-10-11T16:00:00+08:00"
+        );
+
+        let local = Timezone::Local.convert(at());
+        assert_eq!(local.with_timezone(&Utc), at());
+    }
+
+    #[test]
+    fn test_timezone_parse() {
+        assert_eq!("UTC".parse::<Timezone>().unwrap(), Timezone::Utc);
+        assert_eq!("local".parse::<Timezone>().unwrap(), Timezone::Local);
+        assert_eq!("+08:00".parse::<Timezone>().unwrap(), Timezone::china());
+        assert!("mars".parse::<Timezone>().is_err());
+    }
+
+    #[test]
+    fn test_with_time_source_is_scoped() {
+        let fixed = TimeSource::fixed(at(), Timezone::Utc);
+
+        let inner = with_time_source(fixed.clone(), || {
+            // 嵌套替换后恢复外层
+            let nested = with_time_source(fixed.clone().with_timezone(Timezone::china()), now);
+            assert_eq!(nested.offset().local_minus_utc(), 8 * 3600);
+            now()
+        });
+        assert_eq!(inner.to_rfc3339(), "2024-10-11T08:00:00+00:00");
+
+        // 离开作用域后恢复为系统时钟
+        assert_ne!(now().with_timezone(&Utc), at());
+    }
+
+    #[test]
+    fn test_envelope_uses_current_time_source() {
+        let msg = with_time_source(TimeSource::fixed(at(), Timezone::china()), || {
+            crate::v0::Envelope::user("测试")
+        });
+        assert_eq!(msg.meta.timestamp.to_rfc3339(), "2024-10-11T16:00:00+08:00");
+
+        let v1_msg = with_time_source(TimeSource::fixed(at(), Timezone::Utc), || {
+            crate::v1::Envelope::user("测试")
+        });
+        assert_eq!(v1_msg.meta.timestamp.with_timezone(&Utc), at());
+    }
+}
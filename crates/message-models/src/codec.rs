@@ -0,0 +1,264 @@
+//! 消息编码
+//!
+//! 除 JSON 外，消息还可以编码为 CBOR 或 MessagePack，以减小事件密集模块的负载体积。
+//! 编码通过 MIME 类型（MQTT v5 的 content-type 属性）标识，接收端据此自动解码；
+//! 缺少 content-type 时按负载的首字节识别。
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::v0::Envelope;
+use crate::version::VersionedEnvelope;
+
+/// 消息编码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Encoding {
+    /// `application/json`
+    #[default]
+    Json,
+    /// `application/cbor`
+    Cbor,
+    /// `application/msgpack`
+    MessagePack,
+}
+
+/// 编解码错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// 无法识别的 content-type
+    UnsupportedContentType(String),
+    /// 编码失败
+    Encode(String),
+    /// 解码失败
+    Decode(String),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedContentType(content_type) => {
+                write!(f, "Unsupported content type: {}", content_type)
+            }
+            Self::Encode(e) => write!(f, "Encode error: {}", e),
+            Self::Decode(e) => write!(f, "Decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+impl Encoding {
+    /// 所有支持的编码
+    pub fn all() -> [Encoding; 3] {
+        [Self::Json, Self::Cbor, Self::MessagePack]
+    }
+
+    /// 对应的 MIME 类型
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Cbor => "application/cbor",
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// 根据 MIME 类型识别编码（忽略大小写与 `;` 之后的参数）
+    pub fn from_content_type(content_type: &str) -> Result<Self, CodecError> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        match mime.as_str() {
+            "application/json" | "text/json" => Ok(Self::Json),
+            "application/cbor" => Ok(Self::Cbor),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Ok(Self::MessagePack)
+            }
+            _ => Err(CodecError::UnsupportedContentType(content_type.to_string())),
+        }
+    }
+
+    /// 按首字节识别编码（消息总是一个对象/映射）
+    ///
+    /// - JSON：`{`（允许前导空白）
+    /// - CBOR：映射（major type 5，`0xa0..=0xbb` 或不定长 `0xbf`）
+    /// - MessagePack：fixmap `0x80..=0x8f`、map16 `0xde`、map32 `0xdf`
+    pub fn detect(payload: &[u8]) -> Option<Self> {
+        let first = *payload.iter().find(|b| !b.is_ascii_whitespace())?;
+        match first {
+            b'{' => Some(Self::Json),
+            0xa0..=0xbb | 0xbf => Some(Self::Cbor),
+            0x80..=0x8f | 0xde | 0xdf => Some(Self::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// 根据可选的 content-type 解析编码，缺失时按负载识别，仍无法识别时视为 JSON
+    pub fn resolve(content_type: Option<&str>, payload: &[u8]) -> Result<Self, CodecError> {
+        match content_type {
+            Some(content_type) => Self::from_content_type(content_type),
+            None => Ok(Self::detect(payload).unwrap_or_default()),
+        }
+    }
+
+    /// 编码任意可序列化的值
+    pub fn encode<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        let encode_err = |e: &dyn fmt::Display| CodecError::Encode(e.to_string());
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| encode_err(&e)),
+            Self::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).map_err(|e| encode_err(&e))?;
+                Ok(buf)
+            }
+            // 使用带字段名的映射形式，与 JSON 结构一致（flatten 字段也依赖映射形式）
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| encode_err(&e)),
+        }
+    }
+
+    /// 解码任意可反序列化的值
+    pub fn decode<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, CodecError> {
+        let decode_err = |e: &dyn fmt::Display| CodecError::Decode(e.to_string());
+        match self {
+            Self::Json => serde_json::from_slice(payload).map_err(|e| decode_err(&e)),
+            Self::Cbor => ciborium::from_reader(payload).map_err(|e| decode_err(&e)),
+            Self::MessagePack => rmp_serde::from_slice(payload).map_err(|e| decode_err(&e)),
+        }
+    }
+
+    /// 解码为 JSON 值（消息解码的中间形式，保证与 JSON 路径的行为一致）
+    pub fn decode_value(&self, payload: &[u8]) -> Result<Value, CodecError> {
+        self.decode(payload)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.content_type())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = CodecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_content_type(s)
+    }
+}
+
+impl Envelope {
+    /// 按指定编码序列化
+    pub fn to_bytes(&self, encoding: Encoding) -> Result<Vec<u8>, CodecError> {
+        encoding.encode(self)
+    }
+
+    /// 按指定编码反序列化（content 必须与消息类型匹配）
+    pub fn from_bytes(encoding: Encoding, payload: &[u8]) -> Result<Self, CodecError> {
+        let value = encoding.decode_value(payload)?;
+        serde_json::from_value(value).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+impl VersionedEnvelope {
+    /// 按指定编码序列化
+    pub fn to_bytes(&self, encoding: Encoding) -> Result<Vec<u8>, CodecError> {
+        match self {
+            Self::V0(envelope) => encoding.encode(envelope),
+            Self::V1(envelope) => encoding.encode(envelope),
+        }
+    }
+
+    /// 按指定编码反序列化，自动检测版本
+    pub fn from_bytes(encoding: Encoding, payload: &[u8]) -> Result<Self, CodecError> {
+        let value = encoding.decode_value(payload)?;
+        Self::from_value(value).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v0::EventContent;
+    use std::collections::HashMap;
+
+    fn event() -> Envelope {
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), serde_json::json!("r-1"));
+        data.insert("count".to_string(), serde_json::json!(3));
+        Envelope::event(EventContent::ok("mod-002", data)).reply_to(&Envelope::user("测试"))
+    }
+
+    #[test]
+    fn test_round_trip_all_encodings() {
+        let envelope = event();
+        for encoding in Encoding::all() {
+            let bytes = envelope.to_bytes(encoding).unwrap();
+            assert_eq!(Encoding::detect(&bytes), Some(encoding), "{}", encoding);
+            assert_eq!(
+                Envelope::from_bytes(encoding, &bytes).unwrap(),
+                envelope,
+                "{}",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_is_smaller() {
+        let envelope = event();
+        let json = envelope.to_bytes(Encoding::Json).unwrap();
+        assert!(envelope.to_bytes(Encoding::Cbor).unwrap().len() < json.len());
+        assert!(envelope.to_bytes(Encoding::MessagePack).unwrap().len() < json.len());
+    }
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let v1 = VersionedEnvelope::V0(event())
+            .upgrade_to(crate::SchemaVersion::V1)
+            .unwrap();
+        for encoding in Encoding::all() {
+            let bytes = v1.to_bytes(encoding).unwrap();
+            let decoded = VersionedEnvelope::from_bytes(encoding, &bytes).unwrap();
+            assert_eq!(decoded, v1);
+        }
+    }
+
+    #[test]
+    fn test_content_type() {
+        for encoding in Encoding::all() {
+            assert_eq!(
+                Encoding::from_content_type(encoding.content_type()),
+                Ok(encoding)
+            );
+        }
+        assert_eq!(
+            Encoding::from_content_type("Application/JSON; charset=utf-8"),
+            Ok(Encoding::Json)
+        );
+        assert_eq!(
+            Encoding::from_content_type("application/vnd.msgpack"),
+            Ok(Encoding::MessagePack)
+        );
+        assert!(matches!(
+            Encoding::from_content_type("text/plain"),
+            Err(CodecError::UnsupportedContentType(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_without_content_type() {
+        let bytes = event().to_bytes(Encoding::Cbor).unwrap();
+        assert_eq!(Encoding::resolve(None, &bytes), Ok(Encoding::Cbor));
+        assert_eq!(Encoding::resolve(None, b"  {}"), Ok(Encoding::Json));
+        assert_eq!(
+            Encoding::resolve(Some("application/json"), &bytes),
+            Ok(Encoding::Json)
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Schema 生成与契约检查
+//!
+//! `resources/schemas` 下的 schema 是手写的对外契约，包含条件规则、长度、格式等
+//! Rust 类型表达不了的约束，因此不直接替换为生成的 schema。这里从 Rust 类型生成
+//! schema（[`generate`]），再与检入的 schema 做结构对比（[`check`]）：
+//!
+//! - 对象的属性集合相同
+//! - 解析器要求的字段在 schema 中也是必填的
+//! - schema 接受的 JSON 类型解析器都能接受
+//! - schema 的 `enum` 取值与 Rust 枚举相同（`const` 只需是其中之一）
+//! - 解析器拒绝未知字段时，schema 也不允许额外属性
+//!
+//! 即：通过 schema 校验的消息一定能被解析，schema 可以比解析器更严格。
+
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::schema::{
+    self, SchemaViolation, ENVELOPE_SCHEMA, EVENT_CONTENT_SCHEMA, USER_CONTENT_SCHEMA,
+};
+use crate::v0::{self, EventContent};
+use crate::v1;
+use crate::version::SchemaVersion;
+
+/// 生成单个类型的 schema
+pub fn schema_for<T: JsonSchema>() -> Value {
+    let gen = SchemaSettings::draft2019_09().into_generator();
+    serde_json::to_value(gen.into_root_schema_for::<T>()).unwrap_or(Value::Null)
+}
+
+/// 从 Rust 类型生成指定版本的 schema：`(文件名, schema)`，与 `resources/schemas` 一一对应
+pub fn generate(version: SchemaVersion) -> [(&'static str, Value); 3] {
+    let envelope = match version {
+        SchemaVersion::V0 => schema_for::<v0::Envelope>(),
+        SchemaVersion::V1 => schema_for::<v1::Envelope>(),
+    };
+    [
+        (ENVELOPE_SCHEMA, envelope),
+        (USER_CONTENT_SCHEMA, schema_for::<String>()),
+        (EVENT_CONTENT_SCHEMA, schema_for::<EventContent>()),
+    ]
+}
+
+/// 检查指定版本检入的 schema 是否与 Rust 类型一致，返回所有不一致项（为空表示一致）
+pub fn check(version: SchemaVersion) -> Vec<SchemaViolation> {
+    generate(version)
+        .iter()
+        .zip(schema::sources(version))
+        .flat_map(
+            |((name, generated), (_, source))| match serde_json::from_str::<Value>(source) {
+                Ok(published) => compare(name, generated, &published),
+                Err(e) => vec![SchemaViolation::new(name, "", e.to_string())],
+            },
+        )
+        .collect()
+}
+
+/// 对比生成的 schema 与检入的 schema，违规项的 `path` 为对应的实例位置
+pub fn compare(name: &str, generated: &Value, published: &Value) -> Vec<SchemaViolation> {
+    let mut comparison = Comparison {
+        name,
+        generated_root: generated,
+        published_root: published,
+        violations: Vec::new(),
+    };
+    comparison.node(generated, published, "");
+    comparison.violations
+}
+
+struct Comparison<'a> {
+    name: &'a str,
+    /// 用于解析 `$ref`
+    generated_root: &'a Value,
+    published_root: &'a Value,
+    violations: Vec<SchemaViolation>,
+}
+
+/// JSON 值的类型名称（与 schema 的 `type` 一致）
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn branches(node: &Value) -> Option<&Vec<Value>> {
+    node.get("anyOf")
+        .or_else(|| node.get("oneOf"))
+        .and_then(Value::as_array)
+}
+
+fn string_set(value: Option<&Value>) -> BTreeSet<&str> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn format_set<T: std::fmt::Display>(set: &BTreeSet<T>) -> String {
+    set.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl<'a> Comparison<'a> {
+    fn report(&mut self, path: &str, message: String) {
+        self.violations
+            .push(SchemaViolation::new(self.name, path, message));
+    }
+
+    /// 解析 `$ref` 与单分支的 `allOf`
+    fn deref(&self, root: &'a Value, mut node: &'a Value) -> &'a Value {
+        loop {
+            if let Some(target) = node
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix('#'))
+                .and_then(|pointer| root.pointer(pointer))
+            {
+                node = target;
+                continue;
+            }
+            match node.get("allOf").and_then(Value::as_array) {
+                Some(all_of) if all_of.len() == 1 => node = &all_of[0],
+                _ => return node,
+            }
+        }
+    }
+
+    /// 节点接受的 JSON 类型，`None` 表示不限
+    fn types(&self, root: &'a Value, node: &'a Value) -> Option<BTreeSet<&'static str>> {
+        let node = self.deref(root, node);
+        if let Some(types) = node.get("type") {
+            let names: Vec<&str> = match types {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => return None,
+            };
+            return Some(
+                [
+                    "null", "boolean", "number", "integer", "string", "array", "object",
+                ]
+                .into_iter()
+                .filter(|t| names.contains(t))
+                .collect(),
+            );
+        }
+        if let Some(values) = self.values(root, node) {
+            return Some(values.into_iter().map(kind).collect());
+        }
+        let mut union = BTreeSet::new();
+        for branch in branches(node)? {
+            union.extend(self.types(root, branch)?);
+        }
+        Some(union)
+    }
+
+    /// `enum` / `const` 的取值
+    fn values(&self, root: &'a Value, node: &'a Value) -> Option<Vec<&'a Value>> {
+        let node = self.deref(root, node);
+        if let Some(values) = node.get("enum").and_then(Value::as_array) {
+            return Some(values.iter().collect());
+        }
+        node.get("const").map(|value| vec![value])
+    }
+
+    /// 对象节点：跳过 `Option<T>` 生成的 `anyOf: [T, null]`
+    fn object(&self, root: &'a Value, node: &'a Value) -> &'a Value {
+        let node = self.deref(root, node);
+        if let Some(branches) = branches(node) {
+            let non_null: Vec<&Value> = branches
+                .iter()
+                .filter(|branch| {
+                    self.types(root, branch)
+                        .is_none_or(|types| types != BTreeSet::from(["null"]))
+                })
+                .collect();
+            if let [only] = non_null.as_slice() {
+                return self.object(root, only);
+            }
+        }
+        node
+    }
+
+    fn node(&mut self, generated: &'a Value, published: &'a Value, path: &str) {
+        let (gen_root, pub_root) = (self.generated_root, self.published_root);
+
+        match (
+            self.types(gen_root, generated),
+            self.types(pub_root, published),
+        ) {
+            (Some(parser), Some(schema)) => {
+                let rejected: BTreeSet<&str> = schema
+                    .into_iter()
+                    .filter(|t| {
+                        !(parser.contains(t) || (*t == "integer" && parser.contains("number")))
+                    })
+                    .collect();
+                if !rejected.is_empty() {
+                    self.report(
+                        path,
+                        format!(
+                            "Schema accepts {} but the parser only accepts {}",
+                            format_set(&rejected),
+                            format_set(&parser)
+                        ),
+                    );
+                }
+            }
+            (Some(parser), None) => self.report(
+                path,
+                format!(
+                    "Schema accepts any type but the parser only accepts {}",
+                    format_set(&parser)
+                ),
+            ),
+            _ => {}
+        }
+
+        if let (Some(parser), Some(schema)) = (
+            self.values(gen_root, generated),
+            self.values(pub_root, published),
+        ) {
+            let parser: BTreeSet<String> = parser.iter().map(ToString::to_string).collect();
+            let schema_set: BTreeSet<String> = schema.iter().map(ToString::to_string).collect();
+            let is_enum = self.deref(pub_root, published).get("enum").is_some();
+            if !schema_set.is_subset(&parser) || (is_enum && schema_set != parser) {
+                self.report(
+                    path,
+                    format!(
+                        "Schema allows [{}] but the parser allows [{}]",
+                        format_set(&schema_set),
+                        format_set(&parser)
+                    ),
+                );
+            }
+        }
+
+        let generated = self.object(gen_root, generated);
+        let published = self.object(pub_root, published);
+        let (Some(gen_props), Some(pub_props)) = (
+            generated.get("properties").and_then(Value::as_object),
+            published.get("properties").and_then(Value::as_object),
+        ) else {
+            return;
+        };
+
+        for key in gen_props.keys().filter(|key| !pub_props.contains_key(*key)) {
+            self.report(
+                path,
+                format!("Property '{}' is missing from the schema", key),
+            );
+        }
+        for key in pub_props.keys().filter(|key| !gen_props.contains_key(*key)) {
+            self.report(
+                path,
+                format!("Property '{}' does not exist in the Rust type", key),
+            );
+        }
+
+        let schema_required = string_set(published.get("required"));
+        for key in string_set(generated.get("required")).difference(&schema_required) {
+            self.report(
+                path,
+                format!(
+                    "Property '{}' is required by the parser but optional in the schema",
+                    key
+                ),
+            );
+        }
+
+        if generated.get("additionalProperties") == Some(&Value::Bool(false))
+            && published.get("additionalProperties") != Some(&Value::Bool(false))
+        {
+            self.report(
+                path,
+                "Schema allows additional properties but the parser rejects them".to_string(),
+            );
+        }
+
+        for (key, gen_prop) in gen_props {
+            if let Some(pub_prop) = pub_props.get(key) {
+                self.node(gen_prop, pub_prop, &format!("{}/{}", path, key));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_published_schemas_match_rust_types() {
+        for version in [SchemaVersion::V0, SchemaVersion::V1] {
+            let violations = check(version);
+            assert!(violations.is_empty(), "{}: {:#?}", version, violations);
+        }
+    }
+
+    #[test]
+    fn test_detects_drift() {
+        let generated = schema_for::<v0::Envelope>();
+        let mut published: Value =
+            serde_json::from_str(schema::sources(SchemaVersion::V0)[0].1).unwrap();
+
+        let meta = &mut published["properties"]["meta"];
+        meta["properties"]
+            .as_object_mut()
+            .unwrap()
+            .remove("session_id");
+        meta["properties"]["trace_id"] = json!({ "type": "string" });
+        meta["properties"]["client_id"] = json!({ "type": ["string", "integer"] });
+        published["properties"]["type"] = json!({ "enum": ["system", "user"] });
+        published["properties"]["meta"]["properties"]["signature"]["required"] = json!([]);
+
+        let violations = compare(ENVELOPE_SCHEMA, &generated, &published);
+        let found = |path: &str, text: &str| {
+            violations
+                .iter()
+                .any(|v| v.path == path && v.message.contains(text))
+        };
+        assert!(
+            found("/meta", "'session_id' is missing"),
+            "{:#?}",
+            violations
+        );
+        assert!(
+            found("/meta", "'trace_id' does not exist"),
+            "{:#?}",
+            violations
+        );
+        assert!(found("/meta/client_id", "integer"), "{:#?}", violations);
+        assert!(found("/type", "event"), "{:#?}", violations);
+        assert!(
+            found("/meta/signature", "'alg' is required"),
+            "{:#?}",
+            violations
+        );
+        assert_eq!(violations.len(), 7, "{:#?}", violations);
+    }
+}
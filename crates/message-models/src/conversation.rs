@@ -0,0 +1,414 @@
+//! 对话记录
+//!
+//! [`Conversation`] 按顺序记录编排器收到的消息（[`Envelope`]）与模型的输出（[`Output`]），
+//! 可以序列化为 JSONL（每行一个 [`Turn`]）持久化、导出为 Markdown 便于查看，
+//! 启用 `ollama` feature 时还可以转换为 Ollama `/api/chat` 的消息列表用于回放。
+
+use chrono::{DateTime, FixedOffset};
+#[cfg(feature = "ollama")]
+use ollama_models::ChatMessage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use crate::clock;
+use crate::v0::{Envelope, MessageContent, MessageType, Output};
+
+/// 对话中的角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// `system` 消息
+    System,
+    /// `user` 消息
+    User,
+    /// 模块发出的 `event` 消息
+    Event,
+    /// 模型（编排器）的输出
+    Assistant,
+}
+
+impl Role {
+    /// 消息类型对应的角色
+    pub fn of(message_type: &MessageType) -> Self {
+        match message_type {
+            MessageType::System => Self::System,
+            MessageType::User => Self::User,
+            MessageType::Event => Self::Event,
+        }
+    }
+
+    /// 序列化时使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Event => "event",
+            Self::Assistant => "assistant",
+        }
+    }
+}
+
+/// 对话中的一轮：一条输入消息或一次模型输出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct Turn {
+    pub role: Role,
+    /// 输入消息（`role` 为 `system` / `user` / `event` 时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub envelope: Option<Envelope>,
+    /// 模型输出（`role` 为 `assistant` 时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub output: Option<Output>,
+    pub timestamp: DateTime<FixedOffset>,
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub additional: HashMap<String, Value>,
+}
+
+impl Turn {
+    /// 输入消息，时间戳取自 `meta.timestamp`
+    pub fn from_envelope(envelope: Envelope) -> Self {
+        Self {
+            role: Role::of(&envelope.message_type),
+            timestamp: envelope.meta.timestamp,
+            envelope: Some(envelope),
+            output: None,
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 模型输出，时间戳为当前时间（见 [`crate::clock`]）
+    pub fn from_output(output: Output) -> Self {
+        Self {
+            role: Role::Assistant,
+            envelope: None,
+            output: Some(output),
+            timestamp: clock::now(),
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 设置时间戳
+    pub fn with_timestamp(mut self, timestamp: DateTime<FixedOffset>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// 验证 `role` 与内容是否一致
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.envelope, &self.output) {
+            (Some(envelope), None) => {
+                let expected = Role::of(&envelope.message_type);
+                if self.role != expected {
+                    return Err(format!(
+                        "Turn with a '{}' message must have role '{}', got '{}'",
+                        envelope.message_type.as_str(),
+                        expected.as_str(),
+                        self.role.as_str()
+                    ));
+                }
+                Ok(())
+            }
+            (None, Some(output)) => {
+                if self.role != Role::Assistant {
+                    return Err(format!(
+                        "Turn with an output must have role 'assistant', got '{}'",
+                        self.role.as_str()
+                    ));
+                }
+                output.validate()
+            }
+            (Some(_), Some(_)) => {
+                Err("Turn cannot have both an envelope and an output".to_string())
+            }
+            (None, None) => Err("Turn must have an envelope or an output".to_string()),
+        }
+    }
+
+    /// 转换为 Ollama `/api/chat` 消息
+    ///
+    /// 输入消息以紧凑 JSON 作为内容（`system` 消息使用 system 角色，其余为 user），
+    /// 模型输出以紧凑 JSON 作为 assistant 内容，与系统提示约定的输入输出格式一致
+    #[cfg(feature = "ollama")]
+    pub fn to_chat_message(&self) -> Result<ChatMessage, serde_json::Error> {
+        if let Some(output) = &self.output {
+            return Ok(ChatMessage::assistant(output.to_json()?));
+        }
+
+        let content = match &self.envelope {
+            Some(envelope) => envelope.to_json()?,
+            None => String::new(),
+        };
+        Ok(match self.role {
+            Role::System => ChatMessage::system(content),
+            Role::Assistant => ChatMessage::assistant(content),
+            Role::User | Role::Event => ChatMessage::user(content),
+        })
+    }
+
+    /// Markdown 形式的正文
+    fn markdown_body(&self) -> String {
+        fn json_block(value: &impl Serialize) -> String {
+            let json = serde_json::to_string_pretty(value).unwrap_or_default();
+            format!("```json\n{}\n```", json)
+        }
+
+        if let Some(output) = &self.output {
+            return match output {
+                Output::Reply { content } => content.clone(),
+                Output::Noop { content: None } => "_noop_".to_string(),
+                other => json_block(other),
+            };
+        }
+
+        match self.envelope.as_ref().map(|envelope| &envelope.content) {
+            Some(MessageContent::Text(text)) => text.clone(),
+            Some(MessageContent::Event(event)) => {
+                format!(
+                    "`{}` → `{}`\n\n{}",
+                    event.source,
+                    event.status.as_str(),
+                    json_block(event)
+                )
+            }
+            Some(content) => json_block(content),
+            None => String::new(),
+        }
+    }
+}
+
+/// 对话记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct Conversation {
+    /// 会话 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub session_id: Option<String>,
+    /// 按时间顺序排列的对话轮次
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+}
+
+impl Conversation {
+    /// 创建空的对话
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置会话 ID
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// 追加一条输入消息
+    pub fn push_envelope(&mut self, envelope: Envelope) {
+        self.turns.push(Turn::from_envelope(envelope));
+    }
+
+    /// 追加一次模型输出
+    pub fn push_output(&mut self, output: Output) {
+        self.turns.push(Turn::from_output(output));
+    }
+
+    /// 追加一轮
+    pub fn push(&mut self, turn: Turn) {
+        self.turns.push(turn);
+    }
+
+    /// 轮次数量
+    pub fn len(&self) -> usize {
+        self.turns.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    /// 最后一轮
+    pub fn last(&self) -> Option<&Turn> {
+        self.turns.last()
+    }
+
+    /// 验证所有轮次
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, turn) in self.turns.iter().enumerate() {
+            turn.validate()
+                .map_err(|e| format!("Invalid turn {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 序列化为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// 序列化为 JSONL：每行一个 [`Turn`]（不包含 `session_id`）
+    pub fn to_jsonl(&self) -> Result<String, serde_json::Error> {
+        let mut out = String::new();
+        for turn in &self.turns {
+            out.push_str(&serde_json::to_string(turn)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// 从 JSONL 解析（忽略空行），并验证每一轮
+    pub fn from_jsonl(jsonl: &str) -> Result<Self, String> {
+        let mut conversation = Self::new();
+        for (i, line) in jsonl.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let turn: Turn =
+                serde_json::from_str(line).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            turn.validate()
+                .map_err(|e| format!("Line {}: {}", i + 1, e))?;
+            conversation.push(turn);
+        }
+        Ok(conversation)
+    }
+
+    /// 导出为 Markdown，便于查看
+    pub fn to_markdown(&self) -> String {
+        let mut out = match &self.session_id {
+            Some(session_id) => format!("# Conversation `{}`\n", session_id),
+            None => "# Conversation\n".to_string(),
+        };
+        for (i, turn) in self.turns.iter().enumerate() {
+            out.push_str(&format!(
+                "\n## {}. {} · {}\n\n{}\n",
+                i + 1,
+                turn.role.as_str(),
+                turn.timestamp.format("%Y-%m-%d %H:%M:%S %:z"),
+                turn.markdown_body()
+            ));
+        }
+        out
+    }
+
+    /// 转换为 Ollama `/api/chat` 消息列表，`system_prompt` 作为第一条 system 消息
+    #[cfg(feature = "ollama")]
+    pub fn to_chat_messages(
+        &self,
+        system_prompt: Option<&str>,
+    ) -> Result<Vec<ChatMessage>, serde_json::Error> {
+        let mut messages = Vec::with_capacity(self.turns.len() + 1);
+        if let Some(prompt) = system_prompt {
+            messages.push(ChatMessage::system(prompt));
+        }
+        for turn in &self.turns {
+            messages.push(turn.to_chat_message()?);
+        }
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v0::EventContent;
+
+    fn conversation() -> Conversation {
+        let user = Envelope::user("明天早上八点提醒我开会");
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), serde_json::json!("r-1"));
+        let event = Envelope::event(EventContent::ok("mod-002", data)).reply_to(&user);
+
+        let mut params = HashMap::new();
+        params.insert("time".to_string(), serde_json::json!("08:00"));
+
+        let mut conversation = Conversation::new().with_session_id("s-1");
+        conversation.push_envelope(user);
+        conversation.push_output(Output::command("reminder.create", "mod-002", params));
+        conversation.push_envelope(event);
+        conversation.push_output(Output::reply("已为你设置提醒"));
+        conversation
+    }
+
+    #[test]
+    fn test_roles() {
+        let conversation = conversation();
+        let roles: Vec<Role> = conversation.turns.iter().map(|t| t.role).collect();
+        assert_eq!(
+            roles,
+            [Role::User, Role::Assistant, Role::Event, Role::Assistant]
+        );
+        assert!(conversation.validate().is_ok());
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let conversation = conversation();
+        let jsonl = conversation.to_jsonl().unwrap();
+        assert_eq!(jsonl.lines().count(), 4);
+
+        let parsed = Conversation::from_jsonl(&format!("{}\n\n", jsonl)).unwrap();
+        assert_eq!(parsed.turns, conversation.turns);
+        assert_eq!(parsed.session_id, None);
+
+        let json = conversation.to_json().unwrap();
+        assert_eq!(Conversation::from_json(&json).unwrap(), conversation);
+    }
+
+    #[test]
+    fn test_jsonl_errors() {
+        let err = Conversation::from_jsonl("\n{").unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{}", err);
+
+        let turn = r#"{"role":"assistant","envelope":{"type":"user","content":"hi"},"timestamp":"2024-10-11T08:00:00+08:00"}"#;
+        let err = Conversation::from_jsonl(turn).unwrap_err();
+        assert!(err.contains("role 'user'"), "{}", err);
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = conversation().to_markdown();
+        assert!(markdown.starts_with("# Conversation `s-1`"));
+        assert!(markdown.contains("## 1. user"));
+        assert!(markdown.contains("明天早上八点提醒我开会"));
+        assert!(markdown.contains("\"intent\": \"reminder.create\""));
+        assert!(markdown.contains("`mod-002` → `ok`"));
+        assert!(markdown.contains("## 4. assistant"));
+    }
+
+    #[test]
+    #[cfg(feature = "ollama")]
+    fn test_chat_messages() {
+        use ollama_models::ChatRole;
+
+        let messages = conversation().to_chat_messages(Some("你是编排器")).unwrap();
+        let roles: Vec<ChatRole> = messages.iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                ChatRole::System,
+                ChatRole::User,
+                ChatRole::Assistant,
+                ChatRole::User,
+                ChatRole::Assistant
+            ]
+        );
+        assert_eq!(messages[0].content, "你是编排器");
+
+        // 输入与输出都以 JSON 形式回放
+        assert_eq!(
+            Envelope::from_json(&messages[1].content).unwrap().content,
+            MessageContent::Text("明天早上八点提醒我开会".to_string())
+        );
+        assert_eq!(
+            Output::from_json(&messages[4].content).unwrap(),
+            Output::reply("已为你设置提醒")
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! 错误码目录
+//!
+//! `EventError.code` 使用 [`ErrorCode`]：内置错误码带有分类、是否可重试以及默认的
+//! 用户提示文案键；未知错误码原样保留在 [`ErrorCode::Other`] 中，序列化时不丢失。
+//! 模块自定义的错误码可以通过 [`ErrorCatalog`] 注册元信息。
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use ts_rs::TS;
+
+/// 错误分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    /// 超时
+    Timeout,
+    /// 输入不合法
+    Validation,
+    /// 服务或资源暂不可用
+    Unavailable,
+    /// 权限不足或未认证
+    Permission,
+    /// 未登记的错误码
+    Unknown,
+}
+
+/// 错误码的元信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ErrorCodeInfo {
+    pub category: ErrorCategory,
+    /// 是否值得重试
+    pub retryable: bool,
+    /// 默认的用户提示文案键（如 `error.timeout`）
+    pub message_key: String,
+}
+
+impl ErrorCodeInfo {
+    pub fn new(category: ErrorCategory, retryable: bool, message_key: impl Into<String>) -> Self {
+        Self {
+            category,
+            retryable,
+            message_key: message_key.into(),
+        }
+    }
+
+    /// 未登记错误码使用的元信息：不可重试
+    pub fn unknown() -> Self {
+        Self::new(ErrorCategory::Unknown, false, "error.unknown")
+    }
+}
+
+/// 错误码
+///
+/// 序列化为字符串（如 `"E_TIMEOUT"`），未知错误码保存在 `Other` 中
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(from = "String", into = "String")]
+#[ts(type = "string")]
+pub enum ErrorCode {
+    /// `E_TIMEOUT`
+    Timeout,
+    /// `E_INVALID_PARAMS`
+    InvalidParams,
+    /// `E_UNKNOWN_TARGET`
+    UnknownTarget,
+    /// `E_UNKNOWN_INTENT`
+    UnknownIntent,
+    /// `E_UNAVAILABLE`
+    Unavailable,
+    /// `E_RATE_LIMITED`
+    RateLimited,
+    /// `E_PERMISSION_DENIED`
+    PermissionDenied,
+    /// `E_UNAUTHENTICATED`
+    Unauthenticated,
+    /// 未内置的错误码（原样保留）
+    Other(String),
+}
+
+impl ErrorCode {
+    /// 所有内置错误码
+    pub const BUILTIN: [ErrorCode; 8] = [
+        Self::Timeout,
+        Self::InvalidParams,
+        Self::UnknownTarget,
+        Self::UnknownIntent,
+        Self::Unavailable,
+        Self::RateLimited,
+        Self::PermissionDenied,
+        Self::Unauthenticated,
+    ];
+
+    /// 错误码字符串
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Timeout => "E_TIMEOUT",
+            Self::InvalidParams => "E_INVALID_PARAMS",
+            Self::UnknownTarget => "E_UNKNOWN_TARGET",
+            Self::UnknownIntent => "E_UNKNOWN_INTENT",
+            Self::Unavailable => "E_UNAVAILABLE",
+            Self::RateLimited => "E_RATE_LIMITED",
+            Self::PermissionDenied => "E_PERMISSION_DENIED",
+            Self::Unauthenticated => "E_UNAUTHENTICATED",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// 内置错误码的元信息，`Other` 返回 `None`
+    pub fn builtin_info(&self) -> Option<ErrorCodeInfo> {
+        use ErrorCategory::*;
+
+        let (category, retryable, message_key) = match self {
+            Self::Timeout => (Timeout, true, "error.timeout"),
+            Self::InvalidParams => (Validation, false, "error.invalid_params"),
+            Self::UnknownTarget => (Validation, false, "error.unknown_target"),
+            Self::UnknownIntent => (Validation, false, "error.unknown_intent"),
+            Self::Unavailable => (Unavailable, true, "error.unavailable"),
+            Self::RateLimited => (Unavailable, true, "error.rate_limited"),
+            Self::PermissionDenied => (Permission, false, "error.permission_denied"),
+            Self::Unauthenticated => (Permission, false, "error.unauthenticated"),
+            Self::Other(_) => return None,
+        };
+        Some(ErrorCodeInfo::new(category, retryable, message_key))
+    }
+
+    /// 元信息（未知错误码使用 [`ErrorCodeInfo::unknown`]）
+    pub fn info(&self) -> ErrorCodeInfo {
+        self.builtin_info().unwrap_or_else(ErrorCodeInfo::unknown)
+    }
+
+    /// 错误分类
+    pub fn category(&self) -> ErrorCategory {
+        self.info().category
+    }
+
+    /// 是否值得重试
+    pub fn is_retryable(&self) -> bool {
+        self.info().retryable
+    }
+
+    /// 是否为内置错误码
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        Self::BUILTIN
+            .into_iter()
+            .find(|builtin| builtin.as_str() == code)
+            .unwrap_or_else(|| Self::Other(code.to_string()))
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        match Self::from(code.as_str()) {
+            Self::Other(_) => Self::Other(code),
+            builtin => builtin,
+        }
+    }
+}
+
+impl From<ErrorCode> for String {
+    fn from(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::Other(code) => code,
+            builtin => builtin.as_str().to_string(),
+        }
+    }
+}
+
+impl JsonSchema for ErrorCode {
+    fn schema_name() -> String {
+        "ErrorCode".to_string()
+    }
+
+    /// 与序列化格式一致：任意字符串
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for ErrorCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ErrorCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// 错误码目录
+///
+/// 内置错误码之外，模块可以为自己的错误码登记元信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorCatalog {
+    custom: HashMap<String, ErrorCodeInfo>,
+}
+
+impl ErrorCatalog {
+    /// 只包含内置错误码的目录
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记自定义错误码，不能覆盖内置错误码或重复登记
+    pub fn register(&mut self, code: impl Into<String>, info: ErrorCodeInfo) -> Result<(), String> {
+        let code = code.into();
+        if ErrorCode::from(code.as_str()).is_builtin() {
+            return Err(format!("Error code '{}' is built in", code));
+        }
+        if self.custom.contains_key(&code) {
+            return Err(format!("Error code '{}' is already registered", code));
+        }
+        self.custom.insert(code, info);
+        Ok(())
+    }
+
+    /// 登记自定义错误码（builder 形式）
+    pub fn with_code(
+        mut self,
+        code: impl Into<String>,
+        info: ErrorCodeInfo,
+    ) -> Result<Self, String> {
+        self.register(code, info)?;
+        Ok(self)
+    }
+
+    /// 查找错误码的元信息：内置 → 自定义 → [`ErrorCodeInfo::unknown`]
+    pub fn lookup(&self, code: &ErrorCode) -> ErrorCodeInfo {
+        code.builtin_info()
+            .or_else(|| self.custom.get(code.as_str()).cloned())
+            .unwrap_or_else(ErrorCodeInfo::unknown)
+    }
+
+    /// 是否登记了该错误码（含内置错误码）
+    pub fn contains(&self, code: &ErrorCode) -> bool {
+        code.is_builtin() || self.custom.contains_key(code.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_round_trip() {
+        for code in ErrorCode::BUILTIN {
+            let json = serde_json::to_string(&code).unwrap();
+            let parsed: ErrorCode = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, code);
+            assert_eq!(ErrorCode::from(code.as_str()), code);
+            assert_ne!(code.category(), ErrorCategory::Unknown);
+        }
+    }
+
+    #[test]
+    fn test_unknown_code_round_trip() {
+        let code: ErrorCode = serde_json::from_str(r#""E001""#).unwrap();
+        assert_eq!(code, ErrorCode::Other("E001".to_string()));
+        assert_eq!(serde_json::to_string(&code).unwrap(), r#""E001""#);
+
+        assert_eq!(code.category(), ErrorCategory::Unknown);
+        assert!(!code.is_retryable());
+        assert_eq!(code.info().message_key, "error.unknown");
+    }
+
+    #[test]
+    fn test_builtin_info() {
+        assert!(ErrorCode::Timeout.is_retryable());
+        assert_eq!(ErrorCode::Timeout.category(), ErrorCategory::Timeout);
+        assert_eq!(
+            ErrorCode::InvalidParams.category(),
+            ErrorCategory::Validation
+        );
+        assert!(!ErrorCode::PermissionDenied.is_retryable());
+        assert_eq!(
+            ErrorCode::RateLimited.info().message_key,
+            "error.rate_limited"
+        );
+    }
+
+    #[test]
+    fn test_catalog() {
+        let catalog = ErrorCatalog::new()
+            .with_code(
+                "E_CALENDAR_BUSY",
+                ErrorCodeInfo::new(ErrorCategory::Unavailable, true, "calendar.busy"),
+            )
+            .unwrap();
+
+        let busy = ErrorCode::from("E_CALENDAR_BUSY");
+        assert!(catalog.contains(&busy));
+        assert!(catalog.lookup(&busy).retryable);
+        assert_eq!(
+            catalog.lookup(&ErrorCode::Timeout),
+            ErrorCode::Timeout.info()
+        );
+        assert_eq!(
+            catalog.lookup(&ErrorCode::from("E001")),
+            ErrorCodeInfo::unknown()
+        );
+
+        let mut catalog = catalog;
+        assert!(catalog
+            .register("E_TIMEOUT", ErrorCodeInfo::unknown())
+            .is_err());
+        assert!(catalog
+            .register("E_CALENDAR_BUSY", ErrorCodeInfo::unknown())
+            .is_err());
+    }
+}
@@ -1,14 +1,46 @@
+pub mod chunk;
+pub mod clock;
+pub mod codec;
+pub mod contract;
+pub mod conversation;
+pub mod error_code;
+pub mod registry;
+pub mod schema;
+pub mod signing;
+pub mod typescript;
 pub mod v0;
+pub mod v1;
 pub mod version;
 
-// 重新导出常用类型，默认使用最新版本 (v0)
+// 重新导出常用类型，默认使用 v0（未携带版本信息的消息按 v0 处理）
 pub use v0::{
-    Envelope, EventContent, EventError, EventStatus, MessageContent, MessageMeta, MessageType,
+    Command, Envelope, EventContent, EventError, EventStatus, MessageContent, MessageMeta,
+    MessageType, Output,
 };
+pub use chunk::{Assembled, Chunk, ChunkError, Reassembler};
+pub use clock::{Clock, FixedClock, SystemClock, TimeSource, Timezone};
+pub use codec::{CodecError, Encoding};
+pub use conversation::{Conversation, Role, Turn};
+pub use error_code::{ErrorCatalog, ErrorCategory, ErrorCode, ErrorCodeInfo};
+pub use registry::{
+    tool_name, validate_command, CommandError, EventSpec, IntentSpec, ModuleSpec, Registry,
+};
+pub use schema::{SchemaSet, SchemaViolation};
+pub use signing::{Signature, SignatureError, Signer, Verifier};
 pub use version::{SchemaVersion, VersionedEnvelope};
 
 /// 库的预导入模块
 pub mod prelude {
     pub use crate::v0::*;
+    pub use crate::chunk::{Assembled, Chunk, ChunkError, Reassembler};
+    pub use crate::clock::{Clock, FixedClock, SystemClock, TimeSource, Timezone};
+    pub use crate::codec::{CodecError, Encoding};
+    pub use crate::conversation::{Conversation, Role, Turn};
+    pub use crate::error_code::{ErrorCatalog, ErrorCategory, ErrorCode, ErrorCodeInfo};
+    pub use crate::registry::{
+        tool_name, validate_command, CommandError, EventSpec, IntentSpec, ModuleSpec, Registry,
+    };
+    pub use crate::schema::{SchemaSet, SchemaViolation};
+    pub use crate::signing::{Signature, SignatureError, Signer, Verifier};
     pub use crate::version::{SchemaVersion, VersionedEnvelope};
 }
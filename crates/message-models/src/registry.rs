@@ -0,0 +1,535 @@
+use crate::schema::{self, SchemaViolation};
+use crate::v0::Command;
+#[cfg(feature = "ollama")]
+use ollama_models::{Tool, ToolCall, ToolError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use ts_rs::TS;
+
+/// 意图参数的默认 schema：任意对象
+fn default_params_schema() -> Value {
+    serde_json::json!({ "type": "object" })
+}
+
+/// 编译参数 schema（与消息 schema 一样校验 `format`）
+fn params_validator(schema: &Value) -> Result<jsonschema::Validator, String> {
+    jsonschema::options()
+        .should_validate_formats(true)
+        .build(schema)
+        .map_err(|e| e.to_string())
+}
+
+/// 意图对应的工具名称：`<模块 ID>__<意图名称>`，字母、数字、`_`、`-` 以外的字符替换为 `_`
+///
+/// OpenAI 兼容服务要求函数名称只包含这些字符，因此 `reminder.create` 会变成 `reminder_create`
+pub fn tool_name(module_id: &str, intent: &str) -> String {
+    format!("{}__{}", module_id, intent)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 意图定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct IntentSpec {
+    /// 意图名称（如 `reminder.create`）
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 参数的 JSON Schema
+    #[serde(default = "default_params_schema")]
+    pub params: Value,
+}
+
+impl IntentSpec {
+    /// 创建一个接受任意参数对象的意图
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            params: default_params_schema(),
+        }
+    }
+
+    /// 设置参数的 JSON Schema
+    pub fn with_params(mut self, params: Value) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+/// 模块会发出的事件定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct EventSpec {
+    /// 事件名称（如 `reminder.fired`）
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 事件 `data` 的 JSON Schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub data: Option<Value>,
+}
+
+/// 模块定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModuleSpec {
+    /// 模块 ID（如 `mod-002`），即 command 的 `target`
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub intents: Vec<IntentSpec>,
+    #[serde(default)]
+    pub events: Vec<EventSpec>,
+}
+
+impl ModuleSpec {
+    /// 创建一个没有意图和事件的模块
+    pub fn new(id: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            description: description.into(),
+            intents: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// 添加意图
+    pub fn with_intent(mut self, intent: IntentSpec) -> Self {
+        self.intents.push(intent);
+        self
+    }
+
+    /// 添加事件
+    pub fn with_event(mut self, event: EventSpec) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    /// 按名称查找意图
+    pub fn intent(&self, name: &str) -> Option<&IntentSpec> {
+        self.intents.iter().find(|i| i.name == name)
+    }
+}
+
+/// 已注册模块的集合
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct Registry {
+    #[serde(default)]
+    modules: Vec<ModuleSpec>,
+}
+
+impl Registry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册模块
+    ///
+    /// 模块 ID 不能重复，同一模块内意图名称不能重复，参数 schema 必须合法
+    pub fn register(&mut self, module: ModuleSpec) -> Result<(), String> {
+        if self.get(&module.id).is_some() {
+            return Err(format!("Module '{}' is already registered", module.id));
+        }
+
+        for (i, intent) in module.intents.iter().enumerate() {
+            if module.intents[..i]
+                .iter()
+                .any(|other| other.name == intent.name)
+            {
+                return Err(format!(
+                    "Duplicate intent '{}' in module '{}'",
+                    intent.name, module.id
+                ));
+            }
+            let name = tool_name(&module.id, &intent.name);
+            let collides = self.resolve_tool(&name).is_some()
+                || module.intents[..i]
+                    .iter()
+                    .any(|other| tool_name(&module.id, &other.name) == name);
+            if collides {
+                return Err(format!(
+                    "Tool name '{}' of '{}/{}' is already in use",
+                    name, module.id, intent.name
+                ));
+            }
+            params_validator(&intent.params).map_err(|e| {
+                format!(
+                    "Invalid params schema for '{}/{}': {}",
+                    module.id, intent.name, e
+                )
+            })?;
+        }
+
+        self.modules.push(module);
+        Ok(())
+    }
+
+    /// 按 ID 查找模块
+    pub fn get(&self, id: &str) -> Option<&ModuleSpec> {
+        self.modules.iter().find(|m| m.id == id)
+    }
+
+    /// 所有已注册模块
+    pub fn modules(&self) -> &[ModuleSpec] {
+        &self.modules
+    }
+
+    /// 所有意图对应的工具定义，供模型以结构化的工具调用代替自由格式的 JSON 输出
+    #[cfg(feature = "ollama")]
+    pub fn tools(&self) -> Vec<Tool> {
+        self.modules
+            .iter()
+            .flat_map(|module| {
+                module.intents.iter().map(move |intent| {
+                    let description = if intent.description.is_empty() {
+                        module.description.clone()
+                    } else {
+                        intent.description.clone()
+                    };
+                    Tool::function(
+                        tool_name(&module.id, &intent.name),
+                        description,
+                        intent.params.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// 按工具名称查找模块与意图
+    pub fn resolve_tool(&self, name: &str) -> Option<(&ModuleSpec, &IntentSpec)> {
+        self.modules.iter().find_map(|module| {
+            module
+                .intents
+                .iter()
+                .find(|intent| tool_name(&module.id, &intent.name) == name)
+                .map(|intent| (module, intent))
+        })
+    }
+
+    /// 将模型返回的工具调用转换为 command，并按 [`validate_command`] 校验参数
+    #[cfg(feature = "ollama")]
+    pub fn command_from_tool_call(&self, call: &ToolCall) -> Result<Command, CommandError> {
+        let (module, intent) = self
+            .resolve_tool(call.name())
+            .ok_or_else(|| CommandError::UnknownTool(call.name().to_string()))?;
+        let command = Command {
+            intent: intent.name.clone(),
+            target: module.id.clone(),
+            params: call
+                .function
+                .arguments
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        };
+        validate_command(&command, self)?;
+        Ok(command)
+    }
+
+    /// 从JSON字符串解析（逐个注册，保证与 [`Registry::register`] 相同的约束）
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let parsed: Registry = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut registry = Registry::new();
+        for module in parsed.modules {
+            registry.register(module)?;
+        }
+        Ok(registry)
+    }
+
+    /// 转换为JSON字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+/// command 校验错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    /// 工具名称不对应任何已注册的意图
+    UnknownTool(String),
+    /// `target` 不是已注册的模块
+    UnknownTarget(String),
+    /// 模块中没有该意图
+    UnknownIntent { target: String, intent: String },
+    /// 参数不符合意图的参数 schema
+    InvalidParams {
+        target: String,
+        intent: String,
+        violations: Vec<SchemaViolation>,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTool(name) => write!(f, "Unknown tool: {}", name),
+            Self::UnknownTarget(target) => write!(f, "Unknown target module: {}", target),
+            Self::UnknownIntent { target, intent } => {
+                write!(f, "Unknown intent '{}' for module '{}'", intent, target)
+            }
+            Self::InvalidParams {
+                target,
+                intent,
+                violations,
+            } => {
+                let details: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(
+                    f,
+                    "Invalid params for '{}/{}': {}",
+                    target,
+                    intent,
+                    details.join("; ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// 工具执行器校验调用时使用，错误信息会回传给模型
+#[cfg(feature = "ollama")]
+impl From<CommandError> for ToolError {
+    fn from(e: CommandError) -> Self {
+        match e {
+            CommandError::UnknownTool(name) => ToolError::UnknownTool(name),
+            CommandError::UnknownIntent { target, intent } => {
+                ToolError::UnknownTool(tool_name(&target, &intent))
+            }
+            CommandError::UnknownTarget(_) => ToolError::Rejected(e.to_string()),
+            CommandError::InvalidParams {
+                ref target,
+                ref intent,
+                ..
+            } => ToolError::InvalidArguments {
+                tool: tool_name(target, intent),
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+/// 根据注册表校验 command
+///
+/// 拒绝未注册的 `target`、模块中不存在的 `intent`，以及缺失或不合法的参数
+pub fn validate_command(command: &Command, registry: &Registry) -> Result<(), CommandError> {
+    let module = registry
+        .get(&command.target)
+        .ok_or_else(|| CommandError::UnknownTarget(command.target.clone()))?;
+
+    let intent = module
+        .intent(&command.intent)
+        .ok_or_else(|| CommandError::UnknownIntent {
+            target: command.target.clone(),
+            intent: command.intent.clone(),
+        })?;
+
+    let invalid = |violations| CommandError::InvalidParams {
+        target: command.target.clone(),
+        intent: command.intent.clone(),
+        violations,
+    };
+
+    let schema_name = format!("{}/{}", module.id, intent.name);
+    let validator = params_validator(&intent.params)
+        .map_err(|e| invalid(vec![SchemaViolation::new(&schema_name, "", e)]))?;
+
+    let params = serde_json::to_value(&command.params).unwrap_or(Value::Null);
+    let violations = schema::collect(&validator, &schema_name, &params, "/params");
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry
+            .register(ModuleSpec::new("mod-002", "提醒模块").with_intent(
+                IntentSpec::new("reminder.create", "创建提醒").with_params(json!({
+                    "type": "object",
+                    "required": ["time", "title"],
+                    "properties": {
+                        "time": { "type": "string" },
+                        "title": { "type": "string", "minLength": 1 }
+                    },
+                    "additionalProperties": false
+                })),
+            ))
+            .unwrap();
+        registry
+    }
+
+    fn command(target: &str, intent: &str, params: Value) -> Command {
+        let params: HashMap<String, Value> = serde_json::from_value(params).unwrap();
+        Command {
+            intent: intent.to_string(),
+            target: target.to_string(),
+            params,
+        }
+    }
+
+    #[test]
+    fn test_valid_command() {
+        let cmd = command(
+            "mod-002",
+            "reminder.create",
+            json!({ "time": "07:00", "title": "开会" }),
+        );
+        assert!(validate_command(&cmd, &registry()).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_target_and_intent() {
+        let cmd = command("mod-404", "reminder.create", json!({}));
+        assert_eq!(
+            validate_command(&cmd, &registry()),
+            Err(CommandError::UnknownTarget("mod-404".to_string()))
+        );
+
+        let cmd = command("mod-002", "reminder.delete", json!({}));
+        assert!(matches!(
+            validate_command(&cmd, &registry()),
+            Err(CommandError::UnknownIntent { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let cmd = command(
+            "mod-002",
+            "reminder.create",
+            json!({ "time": "07:00", "extra": 1 }),
+        );
+        match validate_command(&cmd, &registry()) {
+            Err(CommandError::InvalidParams { violations, .. }) => {
+                // 缺少 title + 多余字段 extra
+                assert_eq!(violations.len(), 2, "{:?}", violations);
+                assert!(violations.iter().all(|v| v.path == "/params"));
+            }
+            other => panic!("Expected InvalidParams, got {:?}", other),
+        }
+
+        let cmd = command(
+            "mod-002",
+            "reminder.create",
+            json!({ "time": "07:00", "title": "" }),
+        );
+        match validate_command(&cmd, &registry()) {
+            Err(CommandError::InvalidParams { violations, .. }) => {
+                assert_eq!(violations[0].path, "/params/title");
+            }
+            other => panic!("Expected InvalidParams, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_register_rejects_duplicates_and_bad_schemas() {
+        let mut registry = registry();
+        assert!(registry
+            .register(ModuleSpec::new("mod-002", "重复"))
+            .is_err());
+
+        let duplicate_intent = ModuleSpec::new("mod-003", "")
+            .with_intent(IntentSpec::new("a", ""))
+            .with_intent(IntentSpec::new("a", ""));
+        assert!(registry.register(duplicate_intent).is_err());
+
+        let bad_schema = ModuleSpec::new("mod-004", "")
+            .with_intent(IntentSpec::new("a", "").with_params(json!({ "type": 42 })));
+        assert!(registry.register(bad_schema).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "ollama")]
+    fn test_tools_from_registry() {
+        let registry = registry();
+        let tools = registry.tools();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].function.name, "mod-002__reminder_create");
+        assert_eq!(tools[0].function.description, "创建提醒");
+        assert_eq!(
+            tools[0].function.parameters["required"],
+            json!(["time", "title"])
+        );
+
+        let arguments = json!({ "time": "07:00", "title": "开会" });
+        let call = ToolCall::new(
+            "mod-002__reminder_create",
+            arguments.as_object().unwrap().clone(),
+        );
+        let command = registry.command_from_tool_call(&call).unwrap();
+        assert_eq!(command.target, "mod-002");
+        assert_eq!(command.intent, "reminder.create");
+        assert_eq!(command.params["title"], "开会");
+
+        let unknown = ToolCall::new("mod-002__reminder_delete", Default::default());
+        assert_eq!(
+            registry.command_from_tool_call(&unknown),
+            Err(CommandError::UnknownTool(
+                "mod-002__reminder_delete".to_string()
+            ))
+        );
+
+        let invalid = ToolCall::new("mod-002__reminder_create", Default::default());
+        let err = registry.command_from_tool_call(&invalid).unwrap_err();
+        assert!(matches!(
+            ToolError::from(err),
+            ToolError::InvalidArguments { ref tool, .. } if tool == "mod-002__reminder_create"
+        ));
+        assert_eq!(
+            ToolError::from(CommandError::UnknownIntent {
+                target: "mod-002".to_string(),
+                intent: "reminder.delete".to_string(),
+            }),
+            ToolError::UnknownTool("mod-002__reminder_delete".to_string())
+        );
+        assert_eq!(
+            ToolError::from(CommandError::UnknownTarget("mod-404".to_string())),
+            ToolError::Rejected("Unknown target module: mod-404".to_string())
+        );
+    }
+
+    #[test]
+    fn test_register_rejects_colliding_tool_names() {
+        let mut registry = registry();
+        // 不同模块生成相同的工具名称
+        let first = ModuleSpec::new("mod-006", "").with_intent(IntentSpec::new("x__y", ""));
+        assert!(registry.register(first).is_ok());
+        let second = ModuleSpec::new("mod-006__x", "").with_intent(IntentSpec::new("y", ""));
+        assert!(registry.register(second).is_err());
+
+        let colliding = ModuleSpec::new("mod-005", "")
+            .with_intent(IntentSpec::new("a.b", ""))
+            .with_intent(IntentSpec::new("a_b", ""));
+        assert!(registry
+            .register(colliding)
+            .unwrap_err()
+            .contains("mod-005__a_b"));
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let registry = registry();
+        let json = registry.to_json().unwrap();
+        assert_eq!(Registry::from_json(&json).unwrap(), registry);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::version::SchemaVersion;
+use jsonschema::Validator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::sync::OnceLock;
+use ts_rs::TS;
+
+/// Schema 校验违规项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SchemaViolation {
+    /// 违反的 schema 文件（如 `envelope.json`）
+    pub schema: String,
+    /// 出错位置（JSON Pointer，如 `/content/status`，根为空字符串）
+    pub path: String,
+    /// 错误描述
+    pub message: String,
+}
+
+impl SchemaViolation {
+    pub(crate) fn new(schema: &str, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            schema: schema.to_string(),
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "[{}] {}: {}", self.schema, path, self.message)
+    }
+}
+
+/// 某个 schema 版本对应的一组校验器
+///
+/// - `envelope.json` 校验整个消息
+/// - `user-content.json` 校验 `type = user` 时的 `content`
+/// - `event-content.json` 校验 `type = event` 时的 `content`
+pub struct SchemaSet {
+    version: SchemaVersion,
+    envelope: Validator,
+    user_content: Validator,
+    event_content: Validator,
+}
+
+pub(crate) const ENVELOPE_SCHEMA: &str = "envelope.json";
+pub(crate) const USER_CONTENT_SCHEMA: &str = "user-content.json";
+pub(crate) const EVENT_CONTENT_SCHEMA: &str = "event-content.json";
+
+/// v0 schema 源文件（编译期嵌入）
+const V0_ENVELOPE: &str = include_str!("../../../resources/schemas/v0/envelope.json");
+const V0_USER_CONTENT: &str = include_str!("../../../resources/schemas/v0/user-content.json");
+const V0_EVENT_CONTENT: &str = include_str!("../../../resources/schemas/v0/event-content.json");
+
+/// v1 schema 源文件（编译期嵌入）
+const V1_ENVELOPE: &str = include_str!("../../../resources/schemas/v1/envelope.json");
+const V1_USER_CONTENT: &str = include_str!("../../../resources/schemas/v1/user-content.json");
+const V1_EVENT_CONTENT: &str = include_str!("../../../resources/schemas/v1/event-content.json");
+
+/// 指定版本检入的 schema 源文件：`(文件名, 内容)`
+pub(crate) fn sources(version: SchemaVersion) -> [(&'static str, &'static str); 3] {
+    match version {
+        SchemaVersion::V0 => [
+            (ENVELOPE_SCHEMA, V0_ENVELOPE),
+            (USER_CONTENT_SCHEMA, V0_USER_CONTENT),
+            (EVENT_CONTENT_SCHEMA, V0_EVENT_CONTENT),
+        ],
+        SchemaVersion::V1 => [
+            (ENVELOPE_SCHEMA, V1_ENVELOPE),
+            (USER_CONTENT_SCHEMA, V1_USER_CONTENT),
+            (EVENT_CONTENT_SCHEMA, V1_EVENT_CONTENT),
+        ],
+    }
+}
+
+fn build_validator(name: &str, source: &str) -> Validator {
+    let schema: Value = serde_json::from_str(source)
+        .unwrap_or_else(|e| panic!("Bundled schema {} is not valid JSON: {}", name, e));
+    jsonschema::options()
+        .should_validate_formats(true)
+        .build(&schema)
+        .unwrap_or_else(|e| panic!("Bundled schema {} is invalid: {}", name, e))
+}
+
+impl SchemaSet {
+    /// 获取指定版本的 schema 集合（首次使用时编译并缓存）
+    pub fn for_version(version: SchemaVersion) -> &'static SchemaSet {
+        static V0: OnceLock<SchemaSet> = OnceLock::new();
+        static V1: OnceLock<SchemaSet> = OnceLock::new();
+
+        match version {
+            SchemaVersion::V0 => V0.get_or_init(|| {
+                SchemaSet::build(version, V0_ENVELOPE, V0_USER_CONTENT, V0_EVENT_CONTENT)
+            }),
+            SchemaVersion::V1 => V1.get_or_init(|| {
+                SchemaSet::build(version, V1_ENVELOPE, V1_USER_CONTENT, V1_EVENT_CONTENT)
+            }),
+        }
+    }
+
+    fn build(
+        version: SchemaVersion,
+        envelope: &str,
+        user_content: &str,
+        event_content: &str,
+    ) -> Self {
+        Self {
+            version,
+            envelope: build_validator(ENVELOPE_SCHEMA, envelope),
+            user_content: build_validator(USER_CONTENT_SCHEMA, user_content),
+            event_content: build_validator(EVENT_CONTENT_SCHEMA, event_content),
+        }
+    }
+
+    /// schema 版本
+    pub fn version(&self) -> SchemaVersion {
+        self.version
+    }
+
+    /// 校验一个已解析的 JSON 值，返回所有违规项（为空表示通过）
+    pub fn validate(&self, value: &Value) -> Vec<SchemaViolation> {
+        let mut violations = collect(&self.envelope, ENVELOPE_SCHEMA, value, "");
+
+        // 根据消息类型校验 content
+        let content_validator = match value.get("type").and_then(Value::as_str) {
+            Some("user") => Some((&self.user_content, USER_CONTENT_SCHEMA)),
+            Some("event") => Some((&self.event_content, EVENT_CONTENT_SCHEMA)),
+            _ => None,
+        };
+
+        if let (Some((validator, name)), Some(content)) = (content_validator, value.get("content"))
+        {
+            violations.extend(collect(validator, name, content, "/content"));
+        }
+
+        violations
+    }
+
+    /// 校验 JSON 字符串
+    pub fn validate_json(&self, json: &str) -> Vec<SchemaViolation> {
+        match serde_json::from_str::<Value>(json) {
+            Ok(value) => self.validate(&value),
+            Err(e) => vec![SchemaViolation::new(ENVELOPE_SCHEMA, "", e.to_string())],
+        }
+    }
+}
+
+pub(crate) fn collect(
+    validator: &Validator,
+    schema: &str,
+    instance: &Value,
+    prefix: &str,
+) -> Vec<SchemaViolation> {
+    validator
+        .iter_errors(instance)
+        .map(|e| {
+            SchemaViolation::new(
+                schema,
+                format!("{}{}", prefix, e.instance_path),
+                e.to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_event() {
+        let json = r#"{
+            "type": "event",
+            "content": { "source": "mod-002", "status": "ok", "data": { "id": "r-1" } },
+            "meta": { "schema_version": "v0", "timestamp": "2024-10-11T08:00:00Z" }
+        }"#;
+        let violations = SchemaSet::for_version(SchemaVersion::V0).validate_json(json);
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn test_violation_paths() {
+        let json = r#"{
+            "type": "event",
+            "content": { "source": "", "status": "ok" },
+            "meta": { "timestamp": "not-a-date" }
+        }"#;
+        let violations = SchemaSet::for_version(SchemaVersion::V0).validate_json(json);
+
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert!(paths.contains(&"/meta/timestamp"), "{:?}", violations);
+        assert!(paths.contains(&"/content/source"), "{:?}", violations);
+        // status 为 ok 但缺少 data
+        assert!(violations.iter().any(|v| v.schema == EVENT_CONTENT_SCHEMA
+            && v.path == "/content"
+            && v.message.contains("data")));
+    }
+
+    #[test]
+    fn test_v1_requires_message_id() {
+        let json = r#"{
+            "type": "user",
+            "content": "测试",
+            "meta": { "schema_version": "v1", "timestamp": "2024-10-11T08:00:00Z" }
+        }"#;
+        let violations = SchemaSet::for_version(SchemaVersion::V1).validate_json(json);
+        assert!(violations
+            .iter()
+            .any(|v| v.path == "/meta" && v.message.contains("message_id")));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let violations = SchemaSet::for_version(SchemaVersion::V0).validate_json("{");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "");
+    }
+}
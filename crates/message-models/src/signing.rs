@@ -0,0 +1,349 @@
+//! 消息签名
+//!
+//! 使用每个模块独立的共享密钥对消息做 HMAC-SHA256 签名，签名保存在 `meta.signature` 中：
+//!
+//! ```json
+//! "meta": { ..., "signature": { "alg": "hmac-sha256", "key_id": "mod-002", "value": "<hex>" } }
+//! ```
+//!
+//! 签名内容为去掉 `meta.signature` 之后的整条消息的规范化 JSON（见 [`canonical_json`]）。
+//! 对于 `event` 消息，校验时还要求 `key_id` 与 `content.source` 一致，
+//! 防止模块冒充其他模块发送事件。
+
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use ts_rs::TS;
+
+use crate::v0::Envelope;
+use crate::version::VersionedEnvelope;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 目前支持的签名算法
+pub const HMAC_SHA256: &str = "hmac-sha256";
+
+const SIGNATURE_KEY: &str = "signature";
+
+/// 消息签名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
+pub struct Signature {
+    /// 签名算法（目前只支持 `hmac-sha256`）
+    pub alg: String,
+    /// 密钥 ID，即签名模块的 ID
+    pub key_id: String,
+    /// 十六进制编码的签名值
+    pub value: String,
+}
+
+/// 签名校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// 消息无法解析
+    Malformed(String),
+    /// 消息没有签名
+    Missing,
+    /// 不支持的签名算法
+    UnsupportedAlgorithm(String),
+    /// 没有该密钥 ID 对应的密钥
+    UnknownKey(String),
+    /// 事件的 `source` 与签名的 `key_id` 不一致
+    SourceMismatch { key_id: String, source: String },
+    /// 签名值不匹配
+    Invalid,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(e) => write!(f, "Malformed message: {}", e),
+            Self::Missing => write!(f, "Message is not signed"),
+            Self::UnsupportedAlgorithm(alg) => {
+                write!(f, "Unsupported signature algorithm: {}", alg)
+            }
+            Self::UnknownKey(key_id) => write!(f, "Unknown signing key: {}", key_id),
+            Self::SourceMismatch { key_id, source } => write!(
+                f,
+                "Event source '{}' does not match signing key '{}'",
+                source, key_id
+            ),
+            Self::Invalid => write!(f, "Signature does not match"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// 规范化 JSON：对象键按字典序排列、无多余空白
+///
+/// 同一个 JSON 值在任何一端都得到相同的字节序列，用作签名输入
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// 签名输入：去掉 `meta.signature` 后的规范化 JSON
+fn signing_payload(value: &Value) -> String {
+    let mut value = value.clone();
+    if let Some(meta) = value.get_mut("meta").and_then(Value::as_object_mut) {
+        meta.remove(SIGNATURE_KEY);
+    }
+    canonical_json(&value)
+}
+
+fn mac(secret: &[u8]) -> HmacSha256 {
+    // HMAC 接受任意长度的密钥
+    HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length")
+}
+
+/// 消息签名器（一个模块持有自己的密钥）
+#[derive(Clone)]
+pub struct Signer {
+    key_id: String,
+    secret: Vec<u8>,
+}
+
+impl Signer {
+    /// 使用模块 ID 与共享密钥创建
+    pub fn new(key_id: impl Into<String>, secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            secret: secret.as_ref().to_vec(),
+        }
+    }
+
+    /// 密钥 ID
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// 计算 JSON 值的签名（忽略已有的 `meta.signature`）
+    pub fn signature_for(&self, value: &Value) -> Signature {
+        let mut mac = mac(&self.secret);
+        mac.update(signing_payload(value).as_bytes());
+        Signature {
+            alg: HMAC_SHA256.to_string(),
+            key_id: self.key_id.clone(),
+            value: hex::encode(mac.finalize().into_bytes()),
+        }
+    }
+
+    /// 对消息签名，替换已有的签名
+    pub fn sign(&self, mut envelope: Envelope) -> Envelope {
+        envelope.meta.signature = None;
+        let value = serde_json::to_value(&envelope).unwrap_or(Value::Null);
+        envelope.meta.signature = Some(self.signature_for(&value));
+        envelope
+    }
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 签名校验器，保存各模块的共享密钥
+#[derive(Clone, Default)]
+pub struct Verifier {
+    keys: HashMap<String, Vec<u8>>,
+}
+
+impl Verifier {
+    /// 创建空的校验器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加模块密钥
+    pub fn add_key(&mut self, key_id: impl Into<String>, secret: impl AsRef<[u8]>) {
+        self.keys.insert(key_id.into(), secret.as_ref().to_vec());
+    }
+
+    /// 添加模块密钥（builder 形式）
+    pub fn with_key(mut self, key_id: impl Into<String>, secret: impl AsRef<[u8]>) -> Self {
+        self.add_key(key_id, secret);
+        self
+    }
+
+    /// 校验一个已解析的 JSON 值
+    pub fn verify_value(&self, value: &Value) -> Result<(), SignatureError> {
+        let signature = value
+            .get("meta")
+            .and_then(|meta| meta.get(SIGNATURE_KEY))
+            .ok_or(SignatureError::Missing)?;
+        let signature: Signature = serde_json::from_value(signature.clone())
+            .map_err(|e| SignatureError::Malformed(e.to_string()))?;
+
+        if signature.alg != HMAC_SHA256 {
+            return Err(SignatureError::UnsupportedAlgorithm(signature.alg));
+        }
+
+        let secret = self
+            .keys
+            .get(&signature.key_id)
+            .ok_or_else(|| SignatureError::UnknownKey(signature.key_id.clone()))?;
+
+        // 事件只能由其 source 模块签名
+        if value.get("type").and_then(Value::as_str) == Some("event") {
+            let source = value
+                .pointer("/content/source")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if source != signature.key_id {
+                return Err(SignatureError::SourceMismatch {
+                    key_id: signature.key_id,
+                    source: source.to_string(),
+                });
+            }
+        }
+
+        let expected = hex::decode(&signature.value).map_err(|_| SignatureError::Invalid)?;
+        let mut mac = mac(secret);
+        mac.update(signing_payload(value).as_bytes());
+        mac.verify_slice(&expected)
+            .map_err(|_| SignatureError::Invalid)
+    }
+
+    /// 校验消息
+    pub fn verify(&self, envelope: &Envelope) -> Result<(), SignatureError> {
+        let value =
+            serde_json::to_value(envelope).map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        self.verify_value(&value)
+    }
+
+    /// 按原始 JSON 校验后再解析（推荐用于接收到的消息，不受重新序列化的影响）
+    pub fn verify_json(&self, json: &str) -> Result<VersionedEnvelope, SignatureError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| SignatureError::Malformed(e.to_string()))?;
+        self.verify_value(&value)?;
+        VersionedEnvelope::from_json(json).map_err(|e| SignatureError::Malformed(e.to_string()))
+    }
+}
+
+impl fmt::Debug for Verifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut key_ids: Vec<&String> = self.keys.keys().collect();
+        key_ids.sort();
+        f.debug_struct("Verifier")
+            .field("key_ids", &key_ids)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v0::EventContent;
+    use serde_json::json;
+
+    fn event(source: &str) -> Envelope {
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), json!("r-1"));
+        Envelope::event(EventContent::ok(source, data))
+    }
+
+    fn verifier() -> Verifier {
+        Verifier::new()
+            .with_key("mod-002", "secret-2")
+            .with_key("mod-003", "secret-3")
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let value = json!({ "b": [1, { "d": true, "c": null }], "a": "x" });
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":"x","b":[1,{"c":null,"d":true}]}"#
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signed = Signer::new("mod-002", "secret-2").sign(event("mod-002"));
+        let signature = signed.meta.signature.as_ref().unwrap();
+        assert_eq!(signature.alg, HMAC_SHA256);
+        assert_eq!(signature.key_id, "mod-002");
+
+        assert!(verifier().verify(&signed).is_ok());
+
+        // 按原始 JSON 校验，键顺序与空白不影响结果
+        let pretty = signed.to_json_pretty().unwrap();
+        assert!(verifier().verify_json(&pretty).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_message() {
+        let mut signed = Signer::new("mod-002", "secret-2").sign(event("mod-002"));
+        signed.meta.session_id = Some("s-evil".to_string());
+        assert_eq!(verifier().verify(&signed), Err(SignatureError::Invalid));
+
+        let wrong_secret = Signer::new("mod-002", "guess").sign(event("mod-002"));
+        assert_eq!(
+            verifier().verify(&wrong_secret),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_impersonation_rejected() {
+        // mod-003 用自己的密钥签名，但冒充 mod-002 的事件
+        let forged = Signer::new("mod-003", "secret-3").sign(event("mod-002"));
+        assert!(matches!(
+            verifier().verify(&forged),
+            Err(SignatureError::SourceMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_missing_and_unknown_key() {
+        assert_eq!(
+            verifier().verify(&event("mod-002")),
+            Err(SignatureError::Missing)
+        );
+
+        let signed = Signer::new("mod-404", "x").sign(event("mod-404"));
+        assert_eq!(
+            verifier().verify(&signed),
+            Err(SignatureError::UnknownKey("mod-404".to_string()))
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! TypeScript 类型定义导出
+//!
+//! 前端（`gui/frontend`）使用的类型定义由 [ts-rs](https://docs.rs/ts-rs) 从本 crate 的公开
+//! serde 类型生成，检入在 [`BINDINGS_DIR`]。修改这些类型后需要重新生成：
+//!
+//! ```bash
+//! UPDATE_TS_BINDINGS=1 cargo test -p message-models typescript
+//! ```
+//!
+//! 检入的定义与 Rust 类型不一致时，`typescript` 测试会失败。
+//!
+//! 注意：`#[serde(flatten)] additional` 扩展字段不出现在生成的类型中。
+
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+use crate::chunk::Chunk;
+use crate::conversation::Conversation;
+use crate::error_code::ErrorCodeInfo;
+use crate::registry::Registry;
+use crate::schema::SchemaViolation;
+use crate::v0::{Envelope, Output};
+use crate::v1;
+use crate::version::SchemaVersion;
+
+/// 检入的类型定义目录（相对于 crate 根目录）
+pub const BINDINGS_DIR: &str = "../../gui/frontend/src/types/generated/message-models";
+
+/// 将所有公开类型（及其依赖的类型）导出到 `out_dir`，每个类型一个 `.ts` 文件
+pub fn export_bindings(out_dir: impl AsRef<Path>) -> Result<(), ExportError> {
+    let out_dir = out_dir.as_ref();
+    Envelope::export_all_to(out_dir)?;
+    v1::Envelope::export_all_to(out_dir)?;
+    Output::export_all_to(out_dir)?;
+    SchemaVersion::export_all_to(out_dir)?;
+    Registry::export_all_to(out_dir)?;
+    ErrorCodeInfo::export_all_to(out_dir)?;
+    SchemaViolation::export_all_to(out_dir)?;
+    Chunk::export_all_to(out_dir)?;
+    Conversation::export_all_to(out_dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typescript_bindings_up_to_date() {
+        let checked_in = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_DIR);
+        ts_bindings::check_bindings("message-models", &checked_in, |dir| export_bindings(dir));
+    }
+}
@@ -1,10 +1,17 @@
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, FixedOffset};
+
+use crate::clock::{self, TimeSource, Timezone};
+use crate::error_code::{ErrorCategory, ErrorCode};
+use crate::signing::Signature;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use ts_rs::TS;
 
 /// 消息类型枚举
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageType {
     System,
@@ -12,14 +19,52 @@ pub enum MessageType {
     Event,
 }
 
+impl MessageType {
+    /// 序列化时使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Event => "event",
+        }
+    }
+}
+
 /// 消息元数据
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// 追踪字段的约定：
+/// - `message_id`：消息自身的唯一 ID
+/// - `correlation_id`：一次编排的根消息 ID，同一编排中的所有消息共享
+/// - `causation_id`：直接触发本消息的上一条消息 ID
+/// - `session_id` / `client_id`：所属会话与发起的客户端
+///
+/// `signature` 为可选的消息签名，见 [`crate::signing`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 pub struct MessageMeta {
     #[serde(default = "default_schema_version")]
     pub schema_version: String,
     #[serde(default = "default_timestamp")]
     pub timestamp: DateTime<FixedOffset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub correlation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub causation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub signature: Option<Signature>,
     #[serde(flatten)]
+    #[ts(skip)]
     pub additional: HashMap<String, Value>,
 }
 
@@ -27,14 +72,14 @@ fn default_schema_version() -> String {
     "v0".to_string()
 }
 
-/// 获取东八区时区
-fn china_timezone() -> FixedOffset {
-    FixedOffset::east_opt(8 * 3600).unwrap()
+/// 默认时间戳（当前时间来源，默认为东八区系统时间，见 [`crate::clock`]）
+pub(crate) fn default_timestamp() -> DateTime<FixedOffset> {
+    clock::now()
 }
 
-/// 默认时间戳（东八区当前时间）
-fn default_timestamp() -> DateTime<FixedOffset> {
-    Utc::now().with_timezone(&china_timezone())
+/// 生成新的消息 ID
+pub(crate) fn new_message_id() -> String {
+    uuid::Uuid::new_v4().to_string()
 }
 
 impl Default for MessageMeta {
@@ -42,56 +87,172 @@ impl Default for MessageMeta {
         Self {
             schema_version: default_schema_version(),
             timestamp: default_timestamp(),
+            message_id: None,
+            correlation_id: None,
+            causation_id: None,
+            session_id: None,
+            client_id: None,
+            signature: None,
             additional: HashMap::new(),
         }
     }
 }
 
 impl MessageMeta {
-    /// 创建一个新的 MessageMeta，使用默认的 schema_version (v0) 和东八区当前时间
+    /// 创建一个新的 MessageMeta，使用默认的 schema_version (v0)、当前时间来源和新的 message_id
+    ///
+    /// [`Default`] 不生成 message_id：反序列化时缺失的 `meta` 保持 `message_id: None`
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            message_id: Some(new_message_id()),
+            ..Self::default()
+        }
     }
 
-    /// 使用东八区当前时间创建 MessageMeta
+    /// 使用东八区当前时间创建 MessageMeta（时钟仍取自当前时间来源）
     pub fn with_china_time() -> Self {
+        Self::from_time_source(&clock::current().with_timezone(Timezone::china()))
+    }
+
+    /// 使用指定的时间来源创建 MessageMeta
+    pub fn from_time_source(source: &TimeSource) -> Self {
         Self {
-            schema_version: default_schema_version(),
-            timestamp: Utc::now().with_timezone(&china_timezone()),
-            additional: HashMap::new(),
+            timestamp: source.now(),
+            ..Self::new()
         }
     }
+
+    /// 设置消息 ID
+    pub fn with_message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+
+    /// 设置关联 ID
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// 设置因果 ID
+    pub fn with_causation_id(mut self, causation_id: impl Into<String>) -> Self {
+        self.causation_id = Some(causation_id.into());
+        self
+    }
+
+    /// 设置会话 ID
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// 设置客户端 ID
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// 获取消息 ID，缺失时生成一个并写入
+    pub fn ensure_message_id(&mut self) -> &str {
+        self.message_id.get_or_insert_with(new_message_id)
+    }
 }
 
 /// 事件状态
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EventStatus {
+    /// 成功（需要 data 字段；`final = false` 表示部分结果）
     Ok,
+    /// 失败（需要 error 字段）
     Error,
+    /// 进行中（data 中需要 `percent` 或 `stage`）
+    Progress,
+    /// 已取消
+    Cancelled,
 }
 
+impl EventStatus {
+    /// 序列化时使用的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Progress => "progress",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// 未显式设置 `final` 时，该状态是否为最终事件
+    pub fn is_final_by_default(&self) -> bool {
+        !matches!(self, Self::Progress)
+    }
+}
+
+/// progress 事件 data 中的进度百分比键
+pub const PROGRESS_PERCENT_KEY: &str = "percent";
+/// progress 事件 data 中的阶段键
+pub const PROGRESS_STAGE_KEY: &str = "stage";
+
 /// 错误信息
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 pub struct EventError {
-    pub code: String,
+    /// 错误码（未知错误码原样保留，见 [`ErrorCode`]）
+    pub code: ErrorCode,
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub details: Option<HashMap<String, Value>>,
     #[serde(flatten)]
+    #[ts(skip)]
     pub additional: HashMap<String, Value>,
 }
 
+impl EventError {
+    /// 创建错误信息
+    pub fn new(code: impl Into<ErrorCode>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 设置错误详情
+    pub fn with_details(mut self, details: HashMap<String, Value>) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// 错误分类（使用内置错误码目录）
+    pub fn category(&self) -> ErrorCategory {
+        self.code.category()
+    }
+
+    /// 是否值得重试（使用内置错误码目录）
+    pub fn is_retryable(&self) -> bool {
+        self.code.is_retryable()
+    }
+}
+
 /// 事件内容
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 pub struct EventContent {
     pub source: String,
     pub status: EventStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub data: Option<HashMap<String, Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub error: Option<EventError>,
+    /// 是否为最终事件，未设置时按状态推断（见 [`EventContent::is_terminal`]）
+    #[serde(rename = "final", default, skip_serializing_if = "Option::is_none")]
+    #[ts(rename = "final", optional)]
+    pub is_final: Option<bool>,
     #[serde(flatten)]
+    #[ts(skip)]
     pub additional: HashMap<String, Value>,
 }
 
@@ -103,10 +264,16 @@ impl EventContent {
             status: EventStatus::Ok,
             data: Some(data),
             error: None,
+            is_final: None,
             additional: HashMap::new(),
         }
     }
 
+    /// 创建一个部分结果事件（`ok` + `final = false`）
+    pub fn partial(source: impl Into<String>, data: HashMap<String, Value>) -> Self {
+        Self::ok(source, data).with_final(false)
+    }
+
     /// 创建一个错误状态的事件
     pub fn error(source: impl Into<String>, error: EventError) -> Self {
         Self {
@@ -114,11 +281,77 @@ impl EventContent {
             status: EventStatus::Error,
             data: None,
             error: Some(error),
+            is_final: None,
             additional: HashMap::new(),
         }
     }
 
+    /// 创建一个进度事件，`percent` 与 `stage` 至少提供一个
+    pub fn progress(source: impl Into<String>, percent: Option<f64>, stage: Option<&str>) -> Self {
+        let mut data = HashMap::new();
+        if let Some(percent) = percent {
+            data.insert(PROGRESS_PERCENT_KEY.to_string(), serde_json::json!(percent));
+        }
+        if let Some(stage) = stage {
+            data.insert(
+                PROGRESS_STAGE_KEY.to_string(),
+                Value::String(stage.to_string()),
+            );
+        }
+
+        Self {
+            source: source.into(),
+            status: EventStatus::Progress,
+            data: Some(data),
+            error: None,
+            is_final: None,
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 创建一个已取消事件
+    pub fn cancelled(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            status: EventStatus::Cancelled,
+            data: None,
+            error: None,
+            is_final: None,
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 设置 final 标记
+    pub fn with_final(mut self, is_final: bool) -> Self {
+        self.is_final = Some(is_final);
+        self
+    }
+
+    /// 是否为最终事件
+    ///
+    /// 最终事件应当生成 reply（或继续编排），非最终事件（进度、部分结果）应继续等待
+    pub fn is_terminal(&self) -> bool {
+        self.is_final
+            .unwrap_or_else(|| self.status.is_final_by_default())
+    }
+
+    /// progress 事件的进度百分比
+    pub fn percent(&self) -> Option<f64> {
+        self.data.as_ref()?.get(PROGRESS_PERCENT_KEY)?.as_f64()
+    }
+
+    /// progress 事件的阶段
+    pub fn stage(&self) -> Option<&str> {
+        self.data.as_ref()?.get(PROGRESS_STAGE_KEY)?.as_str()
+    }
+
     /// 验证事件内容的一致性
+    ///
+    /// - `ok`：需要 data
+    /// - `error`：需要 error，且不能标记为非最终
+    /// - `progress`：需要 data，其中 `percent`（0-100 的数字）与 `stage`（非空字符串）至少一个，
+    ///   且不能标记为最终
+    /// - `cancelled`：不能标记为非最终
     pub fn validate(&self) -> Result<(), String> {
         match self.status {
             EventStatus::Ok if self.data.is_none() => {
@@ -127,13 +360,58 @@ impl EventContent {
             EventStatus::Error if self.error.is_none() => {
                 Err("Status is 'error' but 'error' is missing".to_string())
             }
+            EventStatus::Error | EventStatus::Cancelled if self.is_final == Some(false) => {
+                Err(format!("Status '{}' is always final", self.status.as_str()))
+            }
+            EventStatus::Progress => self.validate_progress(),
             _ => Ok(()),
         }
     }
+
+    fn validate_progress(&self) -> Result<(), String> {
+        if self.is_final == Some(true) {
+            return Err("Status 'progress' cannot be final".to_string());
+        }
+
+        let data = self
+            .data
+            .as_ref()
+            .ok_or_else(|| "Status is 'progress' but 'data' is missing".to_string())?;
+
+        let percent = data.get(PROGRESS_PERCENT_KEY);
+        let stage = data.get(PROGRESS_STAGE_KEY);
+        if percent.is_none() && stage.is_none() {
+            return Err("Progress data requires 'percent' or 'stage'".to_string());
+        }
+
+        if let Some(percent) = percent {
+            match percent.as_f64() {
+                Some(p) if (0.0..=100.0).contains(&p) => {}
+                _ => {
+                    return Err(format!(
+                        "Progress 'percent' must be a number in 0..=100, got {}",
+                        percent
+                    ))
+                }
+            }
+        }
+
+        if let Some(stage) = stage {
+            match stage.as_str() {
+                Some(s) if !s.is_empty() => {}
+                _ => return Err("Progress 'stage' must be a non-empty string".to_string()),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// 消息内容（可以是字符串或事件对象）
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// 单独反序列化时按形状猜测变体（宽松模式）；作为 [`Envelope`] 的一部分时，
+/// 由消息类型决定变体，见 [`MessageContent::from_value`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
 #[serde(untagged)]
 pub enum MessageContent {
     /// 用户消息内容（字符串）
@@ -144,8 +422,60 @@ pub enum MessageContent {
     Object(HashMap<String, Value>),
 }
 
+/// JSON 值的类型名称，用于错误信息
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl MessageContent {
+    /// 按消息类型解析内容
+    ///
+    /// - `user`：必须是字符串
+    /// - `event`：必须是合法的 [`EventContent`] 对象
+    /// - `system`：必须是对象
+    pub fn from_value(message_type: &MessageType, value: Value) -> Result<Self, String> {
+        match (message_type, value) {
+            (MessageType::User, Value::String(text)) => Ok(Self::Text(text)),
+            (MessageType::Event, value @ Value::Object(_)) => serde_json::from_value(value)
+                .map(Self::Event)
+                .map_err(|e| format!("Invalid content for 'event' message: {}", e)),
+            (MessageType::System, Value::Object(object)) => {
+                Ok(Self::Object(object.into_iter().collect()))
+            }
+            (message_type, value) => {
+                let expected = match message_type {
+                    MessageType::User => "a string",
+                    MessageType::Event | MessageType::System => "an object",
+                };
+                Err(format!(
+                    "Content of '{}' message must be {}, got {}",
+                    message_type.as_str(),
+                    expected,
+                    value_kind(&value)
+                ))
+            }
+        }
+    }
+
+    /// 按形状猜测变体解析内容（兼容旧数据的宽松模式）
+    pub fn from_value_lenient(value: Value) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(value)
+    }
+}
+
 /// 消息信封（顶层结构）
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// 反序列化时由 `type` 决定 `content` 的变体，类型不匹配时报错；
+/// 需要兼容旧数据时使用 [`Envelope::from_json_lenient`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(try_from = "RawEnvelope<MessageMeta>")]
 pub struct Envelope {
     #[serde(rename = "type")]
     pub message_type: MessageType,
@@ -153,36 +483,129 @@ pub struct Envelope {
     #[serde(default)]
     pub meta: MessageMeta,
     #[serde(flatten)]
+    #[ts(skip)]
     pub additional: HashMap<String, Value>,
 }
 
+/// 尚未解析 content 的信封（v0 与 v1 共用，`M` 为对应版本的元数据）
+#[derive(Deserialize)]
+pub(crate) struct RawEnvelope<M> {
+    #[serde(rename = "type")]
+    message_type: MessageType,
+    content: Value,
+    meta: Option<M>,
+    #[serde(flatten)]
+    additional: HashMap<String, Value>,
+}
+
+/// 可以由 [`RawEnvelope`] 组装的信封
+pub(crate) trait FromRawEnvelope: Sized {
+    type Meta;
+
+    /// `meta` 缺失时的取值
+    fn missing_meta() -> Result<Self::Meta, String>;
+
+    fn from_parts(
+        message_type: MessageType,
+        content: MessageContent,
+        meta: Self::Meta,
+        additional: HashMap<String, Value>,
+    ) -> Self;
+}
+
+impl<M> RawEnvelope<M> {
+    /// 解析 content 并组装信封
+    ///
+    /// 严格模式由 `type` 决定 content 变体；宽松模式按形状猜测，用于兼容旧数据
+    pub(crate) fn into_envelope<E: FromRawEnvelope<Meta = M>>(
+        self,
+        lenient: bool,
+    ) -> Result<E, String> {
+        let content = if lenient {
+            MessageContent::from_value_lenient(self.content).map_err(|e| e.to_string())?
+        } else {
+            MessageContent::from_value(&self.message_type, self.content)?
+        };
+        let meta = match self.meta {
+            Some(meta) => meta,
+            None => E::missing_meta()?,
+        };
+        Ok(E::from_parts(
+            self.message_type,
+            content,
+            meta,
+            self.additional,
+        ))
+    }
+}
+
+/// 宽松解析 JSON 信封（见 [`RawEnvelope::into_envelope`]）
+pub(crate) fn from_json_lenient<E>(json: &str) -> Result<E, serde_json::Error>
+where
+    E: FromRawEnvelope,
+    E::Meta: DeserializeOwned,
+{
+    let raw: RawEnvelope<E::Meta> = serde_json::from_str(json)?;
+    raw.into_envelope(true).map_err(serde::de::Error::custom)
+}
+
+impl FromRawEnvelope for Envelope {
+    type Meta = MessageMeta;
+
+    fn missing_meta() -> Result<MessageMeta, String> {
+        Ok(MessageMeta::default())
+    }
+
+    fn from_parts(
+        message_type: MessageType,
+        content: MessageContent,
+        meta: MessageMeta,
+        additional: HashMap<String, Value>,
+    ) -> Self {
+        Envelope {
+            message_type,
+            content,
+            meta,
+            additional,
+        }
+    }
+}
+
+impl TryFrom<RawEnvelope<MessageMeta>> for Envelope {
+    type Error = String;
+
+    fn try_from(raw: RawEnvelope<MessageMeta>) -> Result<Self, Self::Error> {
+        raw.into_envelope(false)
+    }
+}
+
 impl Envelope {
-    /// 创建用户消息（使用默认 meta）
+    /// 创建用户消息（使用新的 meta，自动生成 message_id）
     pub fn user(content: impl Into<String>) -> Self {
         Self {
             message_type: MessageType::User,
             content: MessageContent::Text(content.into()),
-            meta: MessageMeta::default(),
+            meta: MessageMeta::new(),
             additional: HashMap::new(),
         }
     }
 
-    /// 创建事件消息（使用默认 meta）
+    /// 创建事件消息（使用新的 meta，自动生成 message_id）
     pub fn event(content: EventContent) -> Self {
         Self {
             message_type: MessageType::Event,
             content: MessageContent::Event(content),
-            meta: MessageMeta::default(),
+            meta: MessageMeta::new(),
             additional: HashMap::new(),
         }
     }
 
-    /// 创建系统消息（使用默认 meta）
+    /// 创建系统消息（使用新的 meta，自动生成 message_id）
     pub fn system(content: HashMap<String, Value>) -> Self {
         Self {
             message_type: MessageType::System,
             content: MessageContent::Object(content),
-            meta: MessageMeta::default(),
+            meta: MessageMeta::new(),
             additional: HashMap::new(),
         }
     }
@@ -193,11 +616,53 @@ impl Envelope {
         self
     }
 
-    /// 从JSON字符串解析
+    /// 将本消息标记为 `parent` 的后续消息
+    ///
+    /// - `causation_id` 设为父消息的 `message_id`
+    /// - `correlation_id` 沿用父消息的关联 ID；父消息是编排起点时使用其 `message_id`
+    /// - `session_id`、`client_id` 未设置时从父消息继承
+    /// - 本消息缺少 `message_id` 时自动生成
+    pub fn reply_to(mut self, parent: &Envelope) -> Self {
+        let parent_meta = &parent.meta;
+
+        self.meta.causation_id = parent_meta.message_id.clone();
+        self.meta.correlation_id = parent_meta
+            .correlation_id
+            .clone()
+            .or_else(|| parent_meta.message_id.clone());
+        if self.meta.session_id.is_none() {
+            self.meta.session_id = parent_meta.session_id.clone();
+        }
+        if self.meta.client_id.is_none() {
+            self.meta.client_id = parent_meta.client_id.clone();
+        }
+        self.meta.ensure_message_id();
+        self
+    }
+
+    /// 判断本消息是否与 `other` 属于同一次编排
+    pub fn is_correlated_with(&self, other: &Envelope) -> bool {
+        let root = |e: &Envelope| {
+            e.meta
+                .correlation_id
+                .clone()
+                .or_else(|| e.meta.message_id.clone())
+        };
+        matches!((root(self), root(other)), (Some(a), Some(b)) if a == b)
+    }
+
+    /// 从JSON字符串解析（content 必须与消息类型匹配）
     pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(json)
     }
 
+    /// 从JSON字符串宽松解析
+    ///
+    /// 不校验 content 与消息类型是否匹配，按形状猜测 content 变体，用于兼容旧数据
+    pub fn from_json_lenient(json: &str) -> Result<Self, serde_json::Error> {
+        from_json_lenient(json)
+    }
+
     /// 转换为JSON字符串
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
@@ -209,6 +674,133 @@ impl Envelope {
     }
 }
 
+/// 模块调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct Command {
+    /// 意图名称（如 `reminder.create`）
+    pub intent: String,
+    /// 目标模块 ID（如 `mod-002`）
+    pub target: String,
+    /// 调用参数
+    pub params: HashMap<String, Value>,
+}
+
+/// 编排器（模型）输出
+///
+/// 对应系统提示中约定的三种输出：`command` | `reply` | `noop`，
+/// 每次决策必须且只能输出其中一个 JSON 对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+#[ts(tag = "type", rename_all = "lowercase")]
+pub enum Output {
+    /// 调用模块
+    Command(Command),
+    /// 面向用户的自然语言回复
+    Reply { content: String },
+    /// 仅用于 system 输入的确认
+    Noop {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        content: Option<String>,
+    },
+}
+
+/// 合法的输出类型名称
+const OUTPUT_TYPES: [&str; 3] = ["command", "reply", "noop"];
+
+impl Output {
+    /// 创建模块调用输出
+    pub fn command(
+        intent: impl Into<String>,
+        target: impl Into<String>,
+        params: HashMap<String, Value>,
+    ) -> Self {
+        Self::Command(Command {
+            intent: intent.into(),
+            target: target.into(),
+            params,
+        })
+    }
+
+    /// 创建回复输出
+    pub fn reply(content: impl Into<String>) -> Self {
+        Self::Reply {
+            content: content.into(),
+        }
+    }
+
+    /// 创建确认输出（content 固定为 "ok"）
+    pub fn noop() -> Self {
+        Self::Noop {
+            content: Some("ok".to_string()),
+        }
+    }
+
+    /// 输出类型名称
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Command(_) => "command",
+            Self::Reply { .. } => "reply",
+            Self::Noop { .. } => "noop",
+        }
+    }
+
+    /// 从JSON字符串解析（仅做 serde 反序列化）
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 严格解析模型输出
+    ///
+    /// 要求输入是单个 JSON 对象、`type` 为已知类型、字段齐全且没有多余字段，
+    /// 并通过 [`Output::validate`] 校验
+    pub fn parse_strict(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json.trim())
+            .map_err(|e| format!("Output is not a single JSON value: {}", e))?;
+
+        let object = value
+            .as_object()
+            .ok_or_else(|| "Output must be a single JSON object".to_string())?;
+
+        match object.get("type") {
+            Some(Value::String(t)) if OUTPUT_TYPES.contains(&t.as_str()) => {}
+            Some(Value::String(t)) => return Err(format!("Unknown output type: {}", t)),
+            Some(_) => return Err("Field 'type' must be a string".to_string()),
+            None => return Err("Missing field 'type'".to_string()),
+        }
+
+        let output: Self =
+            serde_json::from_value(value).map_err(|e| format!("Invalid output: {}", e))?;
+        output.validate()?;
+        Ok(output)
+    }
+
+    /// 验证输出内容的一致性
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Command(command) => {
+                if command.intent.trim().is_empty() {
+                    return Err("Command 'intent' must not be empty".to_string());
+                }
+                if command.target.trim().is_empty() {
+                    return Err("Command 'target' must not be empty".to_string());
+                }
+                Ok(())
+            }
+            Self::Reply { content } if content.trim().is_empty() => {
+                Err("Reply 'content' must not be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 转换为JSON字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,7 +840,7 @@ mod tests {
     #[test]
     fn test_event_error() {
         let error = EventError {
-            code: "E001".to_string(),
+            code: ErrorCode::from("E001"),
             message: "处理失败".to_string(),
             details: None,
             additional: HashMap::new(),
@@ -262,6 +854,30 @@ mod tests {
 
         assert!(json.contains("\"status\":\"error\""));
         assert!(json.contains("\"code\":\"E001\""));
+
+        // 未知错误码原样往返
+        let parsed = Envelope::from_json(&json).unwrap();
+        assert_eq!(parsed, msg);
+    }
+
+    #[test]
+    fn test_event_error_code_catalog() {
+        let json = r#"{ "type": "event", "content": { "source": "mod-002", "status": "error", "error": { "code": "E_UNAVAILABLE", "message": "down" } } }"#;
+        let msg = Envelope::from_json(json).unwrap();
+        let error = match &msg.content {
+            MessageContent::Event(event) => event.error.clone().unwrap(),
+            other => panic!("Expected event content, got {:?}", other),
+        };
+
+        assert_eq!(error.code, ErrorCode::Unavailable);
+        assert_eq!(error.category(), ErrorCategory::Unavailable);
+        assert!(error.is_retryable());
+
+        let error = EventError::new(ErrorCode::PermissionDenied, "forbidden");
+        assert!(!error.is_retryable());
+        assert!(serde_json::to_string(&error)
+            .unwrap()
+            .contains("\"E_PERMISSION_DENIED\""));
     }
 
     #[test]
@@ -283,6 +899,7 @@ mod tests {
             status: EventStatus::Ok,
             data: None,
             error: None,
+            is_final: None,
             additional: HashMap::new(),
         };
         assert!(invalid_ok.validate().is_err());
@@ -293,17 +910,69 @@ mod tests {
             status: EventStatus::Error,
             data: None,
             error: None,
+            is_final: None,
             additional: HashMap::new(),
         };
         assert!(invalid_error.validate().is_err());
     }
 
+    #[test]
+    fn test_progress_and_cancelled() {
+        let progress = EventContent::progress("mod-002", Some(40.0), Some("downloading"));
+        assert!(progress.validate().is_ok());
+        assert!(!progress.is_terminal());
+        assert_eq!(progress.percent(), Some(40.0));
+        assert_eq!(progress.stage(), Some("downloading"));
+
+        let json = r#"{ "type": "event", "content": { "source": "mod-002", "status": "progress", "data": { "stage": "parsing" } } }"#;
+        let msg = Envelope::from_json(json).unwrap();
+        match &msg.content {
+            MessageContent::Event(event) => {
+                assert_eq!(event.status, EventStatus::Progress);
+                assert!(event.validate().is_ok());
+            }
+            other => panic!("Expected event content, got {:?}", other),
+        }
+
+        // 缺少 percent/stage、percent 越界、标记为最终
+        assert!(EventContent::progress("mod-002", None, None)
+            .validate()
+            .is_err());
+        assert!(EventContent::progress("mod-002", Some(120.0), None)
+            .validate()
+            .is_err());
+        assert!(EventContent::progress("mod-002", Some(50.0), None)
+            .with_final(true)
+            .validate()
+            .is_err());
+
+        let cancelled = EventContent::cancelled("mod-002");
+        assert!(cancelled.validate().is_ok());
+        assert!(cancelled.is_terminal());
+        assert!(cancelled.with_final(false).validate().is_err());
+    }
+
+    #[test]
+    fn test_final_marker() {
+        let partial = EventContent::partial("mod-002", HashMap::new());
+        assert!(partial.validate().is_ok());
+        assert!(!partial.is_terminal());
+
+        let json = serde_json::to_value(&partial).unwrap();
+        assert_eq!(json["final"], Value::Bool(false));
+
+        // 未设置 final 时不输出该字段
+        let ok = EventContent::ok("mod-002", HashMap::new());
+        assert!(ok.is_terminal());
+        assert!(serde_json::to_value(&ok).unwrap().get("final").is_none());
+    }
+
     #[test]
     fn test_meta() {
         // 测试使用默认值（东八区时间）
         let meta = MessageMeta::new();
         assert_eq!(meta.schema_version, "v0");
-        
+
         // 验证时区是东八区（UTC+8）
         assert_eq!(meta.timestamp.offset().local_minus_utc(), 8 * 3600);
 
@@ -317,6 +986,150 @@ mod tests {
         assert_eq!(parsed.meta.schema_version, "v0");
         assert_eq!(parsed.meta.timestamp.offset().local_minus_utc(), 8 * 3600);
     }
-}
 
+    #[test]
+    fn test_content_must_match_type() {
+        let user_object = r#"{ "type": "user", "content": { "text": "hi" } }"#;
+        let err = Envelope::from_json(user_object).unwrap_err().to_string();
+        assert!(
+            err.contains("'user' message must be a string, got object"),
+            "{}",
+            err
+        );
+
+        let system_string = r#"{ "type": "system", "content": "hi" }"#;
+        let err = Envelope::from_json(system_string).unwrap_err().to_string();
+        assert!(
+            err.contains("'system' message must be an object, got string"),
+            "{}",
+            err
+        );
+
+        // event 内容不合法时不再退化为 Object
+        let bad_event = r#"{ "type": "event", "content": { "source": "mod-002" } }"#;
+        let err = Envelope::from_json(bad_event).unwrap_err().to_string();
+        assert!(
+            err.contains("Invalid content for 'event' message"),
+            "{}",
+            err
+        );
+
+        let system = Envelope::from_json(r#"{ "type": "system", "content": { "k": 1 } }"#).unwrap();
+        assert!(matches!(system.content, MessageContent::Object(_)));
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        let user_object = r#"{ "type": "user", "content": { "text": "hi" } }"#;
+        let msg = Envelope::from_json_lenient(user_object).unwrap();
+        assert_eq!(msg.message_type, MessageType::User);
+        assert!(matches!(msg.content, MessageContent::Object(_)));
+
+        let system_string = r#"{ "type": "system", "content": "hi" }"#;
+        let msg = Envelope::from_json_lenient(system_string).unwrap();
+        assert_eq!(msg.content, MessageContent::Text("hi".to_string()));
+    }
+
+    #[test]
+    fn test_trace_ids_round_trip() {
+        let meta = MessageMeta::new()
+            .with_session_id("s-1")
+            .with_client_id("web-01");
+        let msg = Envelope::user("测试").with_meta(meta);
+        assert!(msg.meta.message_id.is_some());
+
+        let json = msg.to_json().unwrap();
+        assert!(json.contains("\"session_id\":\"s-1\""));
+        assert!(!json.contains("causation_id"));
+
+        let parsed = Envelope::from_json(&json).unwrap();
+        assert_eq!(parsed.meta.client_id.as_deref(), Some("web-01"));
+        assert!(parsed.meta.additional.is_empty());
+    }
+
+    #[test]
+    fn test_parsed_meta_keeps_missing_message_id() {
+        // 新建的消息自动生成 message_id
+        assert!(Envelope::user("测试").meta.message_id.is_some());
+        assert!(MessageMeta::with_china_time().message_id.is_some());
+        assert!(MessageMeta::default().message_id.is_none());
+
+        // 解析得到的消息不补 message_id
+        let parsed = Envelope::from_json(r#"{ "type": "user", "content": "hi" }"#).unwrap();
+        assert!(parsed.meta.message_id.is_none());
+        let parsed =
+            Envelope::from_json(r#"{ "type": "user", "content": "hi", "meta": {} }"#).unwrap();
+        assert!(parsed.meta.message_id.is_none());
+    }
+
+    #[test]
+    fn test_reply_to_propagates_ids() {
+        let user = Envelope::user("明早 7 点提醒我开会").with_meta(
+            MessageMeta::new()
+                .with_message_id("m-1")
+                .with_session_id("s-1")
+                .with_client_id("web-01"),
+        );
+
+        // ai-core 发出的命令
+        let command = Envelope::system(HashMap::new()).reply_to(&user);
+        assert_eq!(command.meta.causation_id.as_deref(), Some("m-1"));
+        assert_eq!(command.meta.correlation_id.as_deref(), Some("m-1"));
+        assert_eq!(command.meta.session_id.as_deref(), Some("s-1"));
+        assert_eq!(command.meta.client_id.as_deref(), Some("web-01"));
+        assert_ne!(command.meta.message_id, user.meta.message_id);
+
+        // mod-002 返回的事件
+        let event = Envelope::event(EventContent::ok("mod-002", HashMap::new())).reply_to(&command);
+        assert_eq!(event.meta.causation_id, command.meta.message_id);
+        assert_eq!(event.meta.correlation_id.as_deref(), Some("m-1"));
+        assert!(event.is_correlated_with(&user));
+        assert!(!event.is_correlated_with(&Envelope::user("另一条消息")));
+    }
+
+    #[test]
+    fn test_output_round_trip() {
+        let mut params = HashMap::new();
+        params.insert("time".to_string(), Value::String("07:00".to_string()));
+        let outputs = vec![
+            Output::command("reminder.create", "mod-002", params),
+            Output::reply("已为你设置提醒"),
+            Output::noop(),
+        ];
+
+        for output in outputs {
+            let json = output.to_json().unwrap();
+            assert!(json.contains(&format!("\"type\":\"{}\"", output.type_name())));
+            assert_eq!(Output::parse_strict(&json).unwrap(), output);
+        }
+    }
 
+    #[test]
+    fn test_output_strict_parsing() {
+        let noop = Output::parse_strict(r#"{"type":"noop","content":"ok"}"#).unwrap();
+        assert_eq!(noop, Output::noop());
+        assert!(Output::parse_strict(r#"{"type":"noop"}"#).is_ok());
+
+        // 多个对象、数组、非对象
+        assert!(Output::parse_strict(r#"{"type":"noop"}{"type":"noop"}"#).is_err());
+        assert!(Output::parse_strict(r#"[{"type":"noop"}]"#).is_err());
+        assert!(Output::parse_strict(r#""ok""#).is_err());
+
+        // 缺少或未知的 type
+        assert!(Output::parse_strict(r#"{"content":"hi"}"#).is_err());
+        assert!(Output::parse_strict(r#"{"type":"event","content":"hi"}"#).is_err());
+
+        // 缺少必填字段、多余字段
+        assert!(
+            Output::parse_strict(r#"{"type":"command","intent":"x","target":"mod-002"}"#).is_err()
+        );
+        assert!(Output::parse_strict(r#"{"type":"reply","content":"hi","intent":"x"}"#).is_err());
+
+        // 空字段
+        assert!(Output::parse_strict(r#"{"type":"reply","content":"  "}"#).is_err());
+        assert!(Output::parse_strict(
+            r#"{"type":"command","intent":"","target":"mod-002","params":{}}"#
+        )
+        .is_err());
+    }
+}
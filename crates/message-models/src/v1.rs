@@ -0,0 +1,367 @@
+//! Schema v1 消息模型
+//!
+//! 与 v0 相比，v1 的 `message_id` 为必填字段，并以 `reply_to` 表示回复目标，
+//! 消息类型与内容结构沿用 v0。
+
+use chrono::{DateTime, FixedOffset};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use crate::signing::Signature;
+use crate::v0::{self, FromRawEnvelope, RawEnvelope};
+pub use crate::v0::{EventContent, EventError, EventStatus, MessageContent, MessageType};
+
+/// v0 中的一等字段，在 v1 中保存在 `meta.additional` 里的键
+const CLIENT_ID_KEY: &str = "client_id";
+
+/// 消息元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[ts(rename = "MessageMetaV1")]
+#[schemars(rename = "MessageMetaV1")]
+pub struct MessageMeta {
+    #[serde(default = "default_schema_version")]
+    pub schema_version: String,
+    /// 消息唯一 ID（必填）
+    pub message_id: String,
+    #[serde(default = "v0::default_timestamp")]
+    pub timestamp: DateTime<FixedOffset>,
+    /// 关联 ID，同一次编排中的所有消息共享
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub correlation_id: Option<String>,
+    /// 回复的目标消息 ID（对应 v0 的 `causation_id`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reply_to: Option<String>,
+    /// 会话 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub session_id: Option<String>,
+    /// 消息签名，见 [`crate::signing`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub signature: Option<Signature>,
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub additional: HashMap<String, Value>,
+}
+
+fn default_schema_version() -> String {
+    "v1".to_string()
+}
+
+impl Default for MessageMeta {
+    fn default() -> Self {
+        Self {
+            schema_version: default_schema_version(),
+            message_id: v0::new_message_id(),
+            timestamp: v0::default_timestamp(),
+            correlation_id: None,
+            reply_to: None,
+            session_id: None,
+            signature: None,
+            additional: HashMap::new(),
+        }
+    }
+}
+
+impl MessageMeta {
+    /// 创建一个新的 MessageMeta，自动生成 message_id
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置关联 ID
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// 设置回复目标
+    pub fn with_reply_to(mut self, reply_to: impl Into<String>) -> Self {
+        self.reply_to = Some(reply_to.into());
+        self
+    }
+
+    /// 设置会话 ID
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// 消息信封（顶层结构）
+///
+/// 与 v0 相同，反序列化时由 `type` 决定 `content` 的变体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+#[serde(try_from = "RawEnvelope<MessageMeta>")]
+#[ts(rename = "EnvelopeV1")]
+#[schemars(rename = "EnvelopeV1")]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub message_type: MessageType,
+    pub content: MessageContent,
+    pub meta: MessageMeta,
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub additional: HashMap<String, Value>,
+}
+
+impl FromRawEnvelope for Envelope {
+    type Meta = MessageMeta;
+
+    fn missing_meta() -> Result<MessageMeta, String> {
+        Err("missing field `meta`".to_string())
+    }
+
+    fn from_parts(
+        message_type: MessageType,
+        content: MessageContent,
+        meta: MessageMeta,
+        additional: HashMap<String, Value>,
+    ) -> Self {
+        Envelope {
+            message_type,
+            content,
+            meta,
+            additional,
+        }
+    }
+}
+
+impl TryFrom<RawEnvelope<MessageMeta>> for Envelope {
+    type Error = String;
+
+    fn try_from(raw: RawEnvelope<MessageMeta>) -> Result<Self, Self::Error> {
+        raw.into_envelope(false)
+    }
+}
+
+impl Envelope {
+    /// 创建用户消息（使用默认 meta）
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::with_content(MessageType::User, MessageContent::Text(content.into()))
+    }
+
+    /// 创建事件消息（使用默认 meta）
+    pub fn event(content: EventContent) -> Self {
+        Self::with_content(MessageType::Event, MessageContent::Event(content))
+    }
+
+    /// 创建系统消息（使用默认 meta）
+    pub fn system(content: HashMap<String, Value>) -> Self {
+        Self::with_content(MessageType::System, MessageContent::Object(content))
+    }
+
+    fn with_content(message_type: MessageType, content: MessageContent) -> Self {
+        Self {
+            message_type,
+            content,
+            meta: MessageMeta::default(),
+            additional: HashMap::new(),
+        }
+    }
+
+    /// 设置元数据
+    pub fn with_meta(mut self, meta: MessageMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// 消息 ID
+    pub fn message_id(&self) -> &str {
+        &self.meta.message_id
+    }
+
+    /// 从JSON字符串解析（content 必须与消息类型匹配）
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 从JSON字符串宽松解析（按形状猜测 content 变体）
+    pub fn from_json_lenient(json: &str) -> Result<Self, serde_json::Error> {
+        v0::from_json_lenient(json)
+    }
+
+    /// 转换为JSON字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// 转换为格式化的JSON字符串
+    pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// 从扩展字段中取出字符串值
+fn take_string(additional: &mut HashMap<String, Value>, key: &str) -> Option<String> {
+    match additional.remove(key) {
+        Some(Value::String(s)) => Some(s),
+        Some(other) => {
+            // 非字符串值原样保留
+            additional.insert(key.to_string(), other);
+            None
+        }
+        None => None,
+    }
+}
+
+/// v0 → v1 升级
+///
+/// 缺少 `message_id` 时自动生成，`causation_id` 映射为 `reply_to`，
+/// `client_id` 保存在 `meta.additional` 中
+impl From<v0::Envelope> for Envelope {
+    fn from(envelope: v0::Envelope) -> Self {
+        let v0_meta = envelope.meta;
+        let mut additional = v0_meta.additional;
+        if let Some(client_id) = v0_meta.client_id {
+            additional.insert(CLIENT_ID_KEY.to_string(), Value::String(client_id));
+        }
+
+        let meta = MessageMeta {
+            schema_version: default_schema_version(),
+            message_id: v0_meta.message_id.unwrap_or_else(v0::new_message_id),
+            timestamp: v0_meta.timestamp,
+            correlation_id: v0_meta.correlation_id,
+            reply_to: v0_meta.causation_id,
+            session_id: v0_meta.session_id,
+            signature: v0_meta.signature,
+            additional,
+        };
+
+        Self {
+            message_type: envelope.message_type,
+            content: envelope.content,
+            meta,
+            additional: envelope.additional,
+        }
+    }
+}
+
+/// v1 → v0 降级
+///
+/// 与升级互逆，因此 v0 → v1 → v0 不丢失信息
+impl From<Envelope> for v0::Envelope {
+    fn from(envelope: Envelope) -> Self {
+        let meta = envelope.meta;
+        let mut additional = meta.additional;
+        let client_id = take_string(&mut additional, CLIENT_ID_KEY);
+
+        v0::Envelope {
+            message_type: envelope.message_type,
+            content: envelope.content,
+            meta: v0::MessageMeta {
+                schema_version: "v0".to_string(),
+                timestamp: meta.timestamp,
+                message_id: Some(meta.message_id),
+                correlation_id: meta.correlation_id,
+                causation_id: meta.reply_to,
+                session_id: meta.session_id,
+                client_id,
+                signature: meta.signature,
+                additional,
+            },
+            additional: envelope.additional,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_message() {
+        let msg = Envelope::user("测试");
+        assert_eq!(msg.meta.schema_version, "v1");
+        assert!(!msg.message_id().is_empty());
+
+        let json = msg.to_json().unwrap();
+        assert!(json.contains("\"message_id\""));
+        assert_eq!(Envelope::from_json(&json).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_message_id_required() {
+        let json = r#"{ "type": "user", "content": "测试", "meta": { "schema_version": "v1" } }"#;
+        assert!(Envelope::from_json(json).is_err());
+
+        // meta 本身也是必填的，宽松模式同样如此
+        let json = r#"{ "type": "user", "content": "测试" }"#;
+        assert!(Envelope::from_json(json).is_err());
+        assert!(Envelope::from_json_lenient(json).is_err());
+    }
+
+    #[test]
+    fn test_content_must_match_type() {
+        let json =
+            r#"{ "type": "user", "content": { "text": "hi" }, "meta": { "message_id": "m-1" } }"#;
+        assert!(Envelope::from_json(json).is_err());
+
+        let lenient = Envelope::from_json_lenient(json).unwrap();
+        assert!(matches!(lenient.content, MessageContent::Object(_)));
+    }
+
+    #[test]
+    fn test_upgrade_lifts_ids() {
+        let json = r#"{
+            "type": "user",
+            "content": "测试",
+            "meta": {
+                "schema_version": "v0",
+                "timestamp": "2024-10-11T08:00:00Z",
+                "message_id": "m-1",
+                "causation_id": "m-0",
+                "session_id": "s-1",
+                "client_id": "web-01",
+                "trace": "keep"
+            }
+        }"#;
+        let v0_envelope = v0::Envelope::from_json(json).unwrap();
+        let upgraded = Envelope::from(v0_envelope.clone());
+
+        assert_eq!(upgraded.meta.schema_version, "v1");
+        assert_eq!(upgraded.meta.message_id, "m-1");
+        assert_eq!(upgraded.meta.reply_to.as_deref(), Some("m-0"));
+        assert_eq!(upgraded.meta.session_id.as_deref(), Some("s-1"));
+        assert_eq!(upgraded.meta.correlation_id, None);
+        assert!(upgraded.meta.additional.contains_key("trace"));
+        assert_eq!(
+            upgraded.meta.additional.get("client_id"),
+            Some(&Value::String("web-01".to_string()))
+        );
+
+        // 再降级回 v0 应与原消息一致
+        let downgraded = v0::Envelope::from(upgraded);
+        assert_eq!(downgraded, v0_envelope);
+    }
+
+    #[test]
+    fn test_upgrade_generates_message_id() {
+        let mut envelope = v0::Envelope::user("测试");
+        envelope.meta.message_id = None;
+        let upgraded = Envelope::from(envelope);
+        assert!(!upgraded.meta.message_id.is_empty());
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let meta = MessageMeta::new()
+            .with_correlation_id("c-1")
+            .with_reply_to("m-0");
+        let msg = Envelope::user("测试").with_meta(meta);
+        let message_id = msg.meta.message_id.clone();
+
+        let downgraded = v0::Envelope::from(msg);
+        assert_eq!(downgraded.meta.schema_version, "v0");
+        assert_eq!(downgraded.meta.message_id, Some(message_id));
+        assert_eq!(downgraded.meta.correlation_id.as_deref(), Some("c-1"));
+        assert_eq!(downgraded.meta.causation_id.as_deref(), Some("m-0"));
+        assert_eq!(downgraded.meta.session_id, None);
+        assert!(downgraded.meta.additional.is_empty());
+    }
+}
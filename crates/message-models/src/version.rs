@@ -1,17 +1,19 @@
+use crate::schema::{SchemaSet, SchemaViolation};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use ts_rs::TS;
 
 /// Schema 版本枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
 #[serde(rename_all = "lowercase")]
 pub enum SchemaVersion {
     /// Schema v0 版本
     #[serde(rename = "v0")]
     V0,
-    // 未来可以添加更多版本:
-    // #[serde(rename = "v1")]
-    // V1,
+    /// Schema v1 版本（消息 ID、关联 ID、回复目标、会话 ID 为一等字段）
+    #[serde(rename = "v1")]
+    V1,
 }
 
 impl Default for SchemaVersion {
@@ -24,6 +26,7 @@ impl fmt::Display for SchemaVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::V0 => write!(f, "v0"),
+            Self::V1 => write!(f, "v1"),
         }
     }
 }
@@ -34,6 +37,7 @@ impl FromStr for SchemaVersion {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "v0" => Ok(Self::V0),
+            "v1" => Ok(Self::V1),
             _ => Err(format!("Unknown schema version: {}", s)),
         }
     }
@@ -42,19 +46,31 @@ impl FromStr for SchemaVersion {
 impl SchemaVersion {
     /// 获取所有支持的版本
     pub fn all() -> Vec<SchemaVersion> {
-        vec![Self::V0]
+        vec![Self::V0, Self::V1]
     }
 
     /// 获取最新版本
     pub fn latest() -> SchemaVersion {
-        Self::V0
+        Self::V1
     }
 
-    /// 检查是否兼容指定版本
+    /// 检查按 `self` 版本实现的模块能否直接解析 `other` 版本的消息
+    ///
+    /// 兼容矩阵（行：读取方，列：消息版本）：
+    ///
+    /// | 读取方 \ 消息 | v0 | v1 |
+    /// |---------------|----|----|
+    /// | v0            | ✅ | ✅ v1 的 `reply_to` 保留在 `additional` 中 |
+    /// | v1            | ❌ 缺少必填的 `message_id`，需先 `upgrade_to(V1)` | ✅ |
+    ///
+    /// 通过 `From` 转换时字段一一对应：`causation_id` ↔ `reply_to`，v0 的 `client_id`
+    /// 保存在 v1 的 `meta.additional["client_id"]` 中，v0 缺少的 `message_id` 在升级时生成
     pub fn is_compatible_with(&self, other: &SchemaVersion) -> bool {
-        // 目前只有 v0，都兼容
-        // 未来可以添加更复杂的兼容性逻辑
-        self == other
+        match (self, other) {
+            (Self::V0, _) => true,
+            (Self::V1, Self::V1) => true,
+            (Self::V1, Self::V0) => false,
+        }
     }
 }
 
@@ -65,8 +81,7 @@ impl SchemaVersion {
 #[derive(Debug, Clone, PartialEq)]
 pub enum VersionedEnvelope {
     V0(crate::v0::Envelope),
-    // 未来版本可以在这里添加:
-    // V1(crate::v1::Envelope),
+    V1(crate::v1::Envelope),
 }
 
 impl VersionedEnvelope {
@@ -80,34 +95,79 @@ impl VersionedEnvelope {
                 let envelope = crate::v0::Envelope::from_json(json)?;
                 Ok(Self::V0(envelope))
             }
+            SchemaVersion::V1 => {
+                let envelope = crate::v1::Envelope::from_json(json)?;
+                Ok(Self::V1(envelope))
+            }
+        }
+    }
+
+    /// 从 JSON 字符串宽松解析，自动检测版本
+    ///
+    /// 不校验 content 与消息类型是否匹配，用于兼容旧数据
+    pub fn from_json_lenient(json: &str) -> Result<Self, serde_json::Error> {
+        match Self::detect_version(json)? {
+            SchemaVersion::V0 => crate::v0::Envelope::from_json_lenient(json).map(Self::V0),
+            SchemaVersion::V1 => crate::v1::Envelope::from_json_lenient(json).map(Self::V1),
+        }
+    }
+
+    /// 从已解析的 JSON 值解析，自动检测版本
+    pub fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        match Self::version_of(&value) {
+            SchemaVersion::V0 => serde_json::from_value(value).map(Self::V0),
+            SchemaVersion::V1 => serde_json::from_value(value).map(Self::V1),
         }
     }
 
     /// 检测 JSON 中的版本信息
     fn detect_version(json: &str) -> Result<SchemaVersion, serde_json::Error> {
-        use serde_json::Value;
-        
-        let value: Value = serde_json::from_str(json)?;
-        
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        Ok(Self::version_of(&value))
+    }
+
+    /// 检测 JSON 值中的版本信息
+    fn version_of(value: &serde_json::Value) -> SchemaVersion {
         // 尝试从 meta.schema_version 获取版本
         if let Some(meta) = value.get("meta") {
             if let Some(schema_version) = meta.get("schema_version") {
                 if let Some(version_str) = schema_version.as_str() {
                     if let Ok(version) = version_str.parse::<SchemaVersion>() {
-                        return Ok(version);
+                        return version;
                     }
                 }
             }
         }
         
         // 默认使用 v0
-        Ok(SchemaVersion::default())
+        SchemaVersion::default()
+    }
+
+    /// 根据检测到的版本，使用对应的 JSON Schema 校验消息
+    ///
+    /// 返回检测到的版本；校验失败时返回所有违规项（带 JSON Pointer 路径）
+    pub fn validate_json(json: &str) -> Result<SchemaVersion, Vec<SchemaViolation>> {
+        let version = Self::detect_version(json).map_err(|e| {
+            vec![SchemaViolation {
+                schema: "envelope.json".to_string(),
+                path: String::new(),
+                message: e.to_string(),
+            }]
+        })?;
+
+        let violations = SchemaSet::for_version(version).validate_json(json);
+        if violations.is_empty() {
+            Ok(version)
+        } else {
+            Err(violations)
+        }
     }
 
     /// 转换为 JSON 字符串
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         match self {
             Self::V0(envelope) => envelope.to_json(),
+            Self::V1(envelope) => envelope.to_json(),
         }
     }
 
@@ -115,6 +175,7 @@ impl VersionedEnvelope {
     pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
         match self {
             Self::V0(envelope) => envelope.to_json_pretty(),
+            Self::V1(envelope) => envelope.to_json_pretty(),
         }
     }
 
@@ -122,6 +183,7 @@ impl VersionedEnvelope {
     pub fn version(&self) -> SchemaVersion {
         match self {
             Self::V0(_) => SchemaVersion::V0,
+            Self::V1(_) => SchemaVersion::V1,
         }
     }
 
@@ -129,6 +191,7 @@ impl VersionedEnvelope {
     pub fn as_v0(&self) -> Option<&crate::v0::Envelope> {
         match self {
             Self::V0(envelope) => Some(envelope),
+            _ => None,
         }
     }
 
@@ -136,21 +199,36 @@ impl VersionedEnvelope {
     pub fn into_v0(self) -> Option<crate::v0::Envelope> {
         match self {
             Self::V0(envelope) => Some(envelope),
+            _ => None,
+        }
+    }
+
+    /// 尝试获取 v1 版本的信封
+    pub fn as_v1(&self) -> Option<&crate::v1::Envelope> {
+        match self {
+            Self::V1(envelope) => Some(envelope),
+            _ => None,
+        }
+    }
+
+    /// 尝试转换为 v1 版本的信封
+    pub fn into_v1(self) -> Option<crate::v1::Envelope> {
+        match self {
+            Self::V1(envelope) => Some(envelope),
+            _ => None,
         }
     }
 
-    /// 升级到指定版本（如果可能）
+    /// 转换到指定版本
+    ///
+    /// 支持 v0 → v1 升级（缺少 `message_id` 时自动生成）和 v1 → v0 降级，
+    /// 两个方向互逆
     pub fn upgrade_to(&self, target: SchemaVersion) -> Result<Self, String> {
-        if self.version() == target {
-            return Ok(self.clone());
+        match (self, target) {
+            (Self::V0(envelope), SchemaVersion::V1) => Ok(Self::V1(envelope.clone().into())),
+            (Self::V1(envelope), SchemaVersion::V0) => Ok(Self::V0(envelope.clone().into())),
+            _ => Ok(self.clone()),
         }
-        
-        // 未来可以在这里添加版本转换逻辑
-        Err(format!(
-            "Cannot upgrade from {} to {}",
-            self.version(),
-            target
-        ))
     }
 }
 
@@ -162,12 +240,14 @@ mod tests {
     fn test_schema_version_parsing() {
         assert_eq!("v0".parse::<SchemaVersion>().unwrap(), SchemaVersion::V0);
         assert_eq!("V0".parse::<SchemaVersion>().unwrap(), SchemaVersion::V0);
-        assert!("v1".parse::<SchemaVersion>().is_err());
+        assert_eq!("v1".parse::<SchemaVersion>().unwrap(), SchemaVersion::V1);
+        assert!("v2".parse::<SchemaVersion>().is_err());
     }
 
     #[test]
     fn test_schema_version_display() {
         assert_eq!(SchemaVersion::V0.to_string(), "v0");
+        assert_eq!(SchemaVersion::V1.to_string(), "v1");
     }
 
     #[test]
@@ -178,6 +258,57 @@ mod tests {
     #[test]
     fn test_schema_version_compatibility() {
         assert!(SchemaVersion::V0.is_compatible_with(&SchemaVersion::V0));
+        assert!(SchemaVersion::V0.is_compatible_with(&SchemaVersion::V1));
+        assert!(SchemaVersion::V1.is_compatible_with(&SchemaVersion::V1));
+        assert!(!SchemaVersion::V1.is_compatible_with(&SchemaVersion::V0));
+    }
+
+    #[test]
+    fn test_versioned_envelope_v1() {
+        let json = r#"{
+            "type": "user",
+            "content": "测试",
+            "meta": {
+                "schema_version": "v1",
+                "message_id": "m-1"
+            }
+        }"#;
+        let versioned = VersionedEnvelope::from_json(json).unwrap();
+        assert_eq!(versioned.version(), SchemaVersion::V1);
+        assert_eq!(versioned.as_v1().unwrap().message_id(), "m-1");
+        assert!(versioned.as_v0().is_none());
+
+        // v0 解析器也能读取 v1 消息
+        let as_v0 = crate::v0::Envelope::from_json(json).unwrap();
+        assert_eq!(as_v0.meta.schema_version, "v1");
+    }
+
+    #[test]
+    fn test_versioned_envelope_lenient() {
+        let json = r#"{ "type": "system", "content": "reload" }"#;
+        assert!(VersionedEnvelope::from_json(json).is_err());
+
+        let versioned = VersionedEnvelope::from_json_lenient(json).unwrap();
+        assert_eq!(versioned.version(), SchemaVersion::V0);
+    }
+
+    #[test]
+    fn test_upgrade_and_downgrade() {
+        let json = r#"{ "type": "user", "content": "测试" }"#;
+        let versioned = VersionedEnvelope::from_json(json).unwrap();
+
+        let upgraded = versioned.upgrade_to(SchemaVersion::V1).unwrap();
+        assert_eq!(upgraded.version(), SchemaVersion::V1);
+        let message_id = upgraded.as_v1().unwrap().message_id().to_string();
+
+        // 升级后的消息可以被重新识别为 v1
+        let reparsed = VersionedEnvelope::from_json(&upgraded.to_json().unwrap()).unwrap();
+        assert_eq!(reparsed, upgraded);
+
+        let downgraded = upgraded.upgrade_to(SchemaVersion::V0).unwrap();
+        assert_eq!(downgraded.version(), SchemaVersion::V0);
+        let meta = &downgraded.as_v0().unwrap().meta;
+        assert_eq!(meta.message_id.as_deref(), Some(message_id.as_str()));
     }
 
     #[test]
@@ -109,12 +109,45 @@ fn test_event_error_fixture_with_version() {
 
             let error = event.error.as_ref().unwrap();
             assert_eq!(error.code, "E_TIMEOUT");
+            assert_eq!(error.category(), ErrorCategory::Timeout);
+            assert!(error.is_retryable());
             assert_eq!(error.message, "scheduler timeout");
         }
         _ => panic!("Expected Event content"),
     }
 }
 
+#[test]
+fn test_progress_and_cancelled_fixtures() {
+    let progress = Envelope::from_json(include_str!("../../../resources/fixtures/event_progress_ok.json")).unwrap();
+    match &progress.content {
+        MessageContent::Event(event) => {
+            assert_eq!(event.status, EventStatus::Progress);
+            assert_eq!(event.percent(), Some(40.0));
+            assert_eq!(event.stage(), Some("downloading"));
+            assert!(!event.is_terminal());
+            assert!(event.validate().is_ok());
+        }
+        _ => panic!("Expected Event content"),
+    }
+
+    let cancelled = Envelope::from_json(include_str!("../../../resources/fixtures/event_cancelled_ok.json")).unwrap();
+    match &cancelled.content {
+        MessageContent::Event(event) => {
+            assert_eq!(event.status, EventStatus::Cancelled);
+            assert!(event.is_terminal());
+            assert!(event.validate().is_ok());
+        }
+        _ => panic!("Expected Event content"),
+    }
+
+    let invalid = Envelope::from_json(include_str!("../../../resources/fixtures/event_progress_invalid.json")).unwrap();
+    match &invalid.content {
+        MessageContent::Event(event) => assert!(event.validate().is_err()),
+        _ => panic!("Expected Event content"),
+    }
+}
+
 #[test]
 fn test_backward_compatibility_without_version() {
     // 测试向后兼容：没有版本信息的消息应该默认使用 v0
@@ -148,3 +181,158 @@ fn test_version_round_trip() {
 }
 
 
+
+#[test]
+fn test_output_command_fixture() {
+    let json = include_str!("../../../resources/fixtures/output_command_ok.json");
+
+    let output = Output::parse_strict(json).unwrap();
+    match output {
+        Output::Command(command) => {
+            assert_eq!(command.intent, "reminder.create");
+            assert_eq!(command.target, "mod-002");
+            assert_eq!(
+                command.params.get("title").and_then(|v| v.as_str()),
+                Some("开会")
+            );
+        }
+        _ => panic!("Expected Command output"),
+    }
+}
+
+#[test]
+fn test_output_reply_and_noop_fixtures() {
+    let reply = include_str!("../../../resources/fixtures/output_reply_ok.json");
+    assert_eq!(
+        Output::parse_strict(reply).unwrap(),
+        Output::reply("已为你设置明早 7 点的开会提醒")
+    );
+
+    let noop = include_str!("../../../resources/fixtures/output_noop_ok.json");
+    assert_eq!(Output::parse_strict(noop).unwrap(), Output::noop());
+}
+
+#[test]
+fn test_output_invalid_fixtures() {
+    let missing_params = include_str!("../../../resources/fixtures/output_command_missing_params.json");
+    assert!(Output::parse_strict(missing_params).is_err());
+
+    let unknown_type = include_str!("../../../resources/fixtures/output_unknown_type.json");
+    let err = Output::parse_strict(unknown_type).unwrap_err();
+    assert!(err.contains("Unknown output type"));
+}
+
+#[test]
+fn test_valid_fixtures_pass_schema() {
+    let fixtures = [
+        include_str!("../../../resources/fixtures/user_text_ok.json"),
+        include_str!("../../../resources/fixtures/event_ok.json"),
+        include_str!("../../../resources/fixtures/event_error.json"),
+        include_str!("../../../resources/fixtures/event_progress_ok.json"),
+        include_str!("../../../resources/fixtures/event_cancelled_ok.json"),
+    ];
+
+    for json in fixtures {
+        let version = VersionedEnvelope::validate_json(json).unwrap();
+        assert_eq!(version, SchemaVersion::V0);
+    }
+}
+
+#[test]
+fn test_invalid_fixtures_fail_schema() {
+    // (fixture, 期望的违规路径)
+    let fixtures = [
+        (
+            include_str!("../../../resources/fixtures/event_missing_data.json"),
+            "/content",
+        ),
+        (
+            include_str!("../../../resources/fixtures/event_unknown_status.json"),
+            "/content/status",
+        ),
+        (
+            include_str!("../../../resources/fixtures/event_progress_invalid.json"),
+            "/content/data/percent",
+        ),
+        (
+            include_str!("../../../resources/fixtures/user_empty_content.json"),
+            "/content",
+        ),
+        (
+            include_str!("../../../resources/fixtures/user_missing_meta.json"),
+            "",
+        ),
+    ];
+
+    for (json, path) in fixtures {
+        let violations = VersionedEnvelope::validate_json(json).unwrap_err();
+        assert!(
+            violations.iter().any(|v| v.path == path),
+            "expected violation at '{}', got {:?}",
+            path,
+            violations
+        );
+    }
+}
+
+#[test]
+fn test_v1_fixtures() {
+    let user = include_str!("../../../resources/fixtures/v1_user_text_ok.json");
+    let event = include_str!("../../../resources/fixtures/v1_event_ok.json");
+
+    for json in [user, event] {
+        assert_eq!(VersionedEnvelope::validate_json(json).unwrap(), SchemaVersion::V1);
+    }
+
+    let versioned = VersionedEnvelope::from_json(event).unwrap();
+    let envelope = versioned.as_v1().unwrap();
+    assert_eq!(envelope.meta.message_id, "m-0003");
+    assert_eq!(envelope.meta.correlation_id.as_deref(), Some("m-0001"));
+    assert_eq!(envelope.meta.reply_to.as_deref(), Some("m-0002"));
+
+    // 降级到 v0 后仍然符合 v0 schema，再升级回 v1 与原消息一致
+    let downgraded = versioned.upgrade_to(SchemaVersion::V0).unwrap();
+    let downgraded_json = downgraded.to_json().unwrap();
+    assert_eq!(
+        VersionedEnvelope::validate_json(&downgraded_json).unwrap(),
+        SchemaVersion::V0
+    );
+    assert_eq!(downgraded.upgrade_to(SchemaVersion::V1).unwrap(), versioned);
+}
+
+#[test]
+fn test_registry_fixture_validates_commands() {
+    let registry = Registry::from_json(include_str!("../../../resources/fixtures/registry_ok.json")).unwrap();
+    let module = registry.get("mod-002").unwrap();
+    assert_eq!(module.events[0].name, "reminder.created");
+
+    let output = Output::parse_strict(include_str!("../../../resources/fixtures/output_command_ok.json")).unwrap();
+    match output {
+        Output::Command(mut command) => {
+            assert!(validate_command(&command, &registry).is_ok());
+
+            // time 必须是 date-time 格式
+            command.params.insert("time".to_string(), "明早 7 点".into());
+            let err = validate_command(&command, &registry).unwrap_err();
+            assert!(matches!(err, CommandError::InvalidParams { .. }), "{}", err);
+        }
+        _ => panic!("Expected Command output"),
+    }
+}
+
+#[test]
+fn test_signed_event_fixture() {
+    let envelope = Envelope::from_json(include_str!("../../../resources/fixtures/event_ok.json")).unwrap();
+    let signed = Signer::new("mod-002", "secret-2").sign(envelope);
+    let json = signed.to_json_pretty().unwrap();
+
+    // 签名后的消息仍符合 schema
+    assert_eq!(VersionedEnvelope::validate_json(&json), Ok(SchemaVersion::V0));
+
+    let verifier = Verifier::new().with_key("mod-002", "secret-2");
+    assert!(verifier.verify_json(&json).is_ok());
+
+    // 签名在 v0 -> v1 转换中保留，但 v1 消息的内容不同，需要重新签名
+    let v1_json = VersionedEnvelope::V0(signed).upgrade_to(SchemaVersion::V1).unwrap().to_json().unwrap();
+    assert_eq!(verifier.verify_json(&v1_json).unwrap_err(), SignatureError::Invalid);
+}
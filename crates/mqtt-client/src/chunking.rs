@@ -0,0 +1,306 @@
+//! 分块发送与重组
+//!
+//! 超过 broker 负载上限的消息拆成多个分块消息发送（content-type 为
+//! [`CHUNK_CONTENT_TYPE`]），接收端用 [`ChunkAssembler`] 还原成完整的 [`MqttMessage`]。
+
+use message_models::chunk::{Chunk, Reassembler, CHUNK_CONTENT_TYPE, DEFAULT_CHUNK_SIZE};
+use rumqttc::v5::mqttbytes::v5::{Publish, PublishProperties};
+use std::time::Duration;
+
+use crate::{MqttClient, MqttMessage, QoS};
+
+impl MqttClient {
+    /// 分块发布负载，返回传输 ID
+    ///
+    /// 负载按 `chunk_size` 字节拆分（`None` 时使用 [`DEFAULT_CHUNK_SIZE`]），
+    /// `content_type` 为完整负载的类型，重组后原样恢复
+    pub async fn publish_chunked(
+        &self,
+        topic: &str,
+        payload: &[u8],
+        content_type: Option<&str>,
+        chunk_size: Option<usize>,
+        qos: QoS,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let chunks = Chunk::split(
+            payload,
+            chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+            content_type,
+        );
+        let transfer_id = chunks[0].transfer_id.clone();
+
+        log::debug!(
+            "📦 Publishing {} bytes to topic {} in {} chunks (transfer {})",
+            payload.len(),
+            topic,
+            chunks.len(),
+            transfer_id
+        );
+
+        for chunk in &chunks {
+            self.publish_with_content_type(topic, &chunk.to_vec()?, CHUNK_CONTENT_TYPE, qos, false)
+                .await?;
+        }
+        Ok(transfer_id)
+    }
+
+    /// 消息不超过 `max_payload_size` 时直接发布，否则分块发布
+    ///
+    /// `max_payload_size` 为 broker 的同名配置，rumqttd 用它限制整个报文的剩余长度，
+    /// 其中除负载外还包括主题、报文 ID 与 content-type 等属性；这部分开销先从上限中扣除，
+    /// 再扣除分块元数据与 base64 开销得出块大小，每条分块消息都不超过上限
+    pub async fn publish_large(
+        &self,
+        topic: &str,
+        payload: &[u8],
+        content_type: &str,
+        max_payload_size: usize,
+        qos: QoS,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if packet_size(topic, content_type, qos, payload.len()) <= max_payload_size {
+            self.publish_with_content_type(topic, payload, content_type, qos, false)
+                .await
+        } else {
+            let chunk_size =
+                chunk_size_for(max_payload_size, topic, qos, payload.len(), content_type)?;
+            self.publish_chunked(topic, payload, Some(content_type), Some(chunk_size), qos)
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+/// 发布消息时 broker 按上限检查的剩余长度：主题、报文 ID、属性与负载
+fn packet_size(topic: &str, content_type: &str, qos: QoS, payload_len: usize) -> usize {
+    let properties = PublishProperties {
+        content_type: Some(content_type.to_string()),
+        ..Default::default()
+    };
+    let mut publish = Publish::new(topic, qos, Vec::new(), Some(properties));
+    if qos != QoS::AtMostOnce {
+        // 报文 ID 在发送时才分配，按占用 2 字节计算
+        publish.pkid = 1;
+    }
+    // size() 含固定头：1 字节类型与剩余长度的编码（负载为空时为 1 字节）
+    publish.size() - 2 + payload_len
+}
+
+/// 分块消息不超过 `max_payload_size` 时每块的数据大小
+fn chunk_size_for(
+    max_payload_size: usize,
+    topic: &str,
+    qos: QoS,
+    payload_len: usize,
+    content_type: &str,
+) -> Result<usize, String> {
+    let too_small = || {
+        format!(
+            "max_payload_size {} is too small to carry chunk metadata",
+            max_payload_size
+        )
+    };
+    let budget = max_payload_size
+        .checked_sub(packet_size(topic, CHUNK_CONTENT_TYPE, qos, 0))
+        .ok_or_else(too_small)?;
+    Chunk::max_data_size(budget, payload_len as u64, Some(content_type)).ok_or_else(too_small)
+}
+
+/// 分块消息重组器
+///
+/// 非分块消息原样返回；分块消息收齐后返回重组的消息，未收齐时返回 `None`
+#[derive(Default)]
+pub struct ChunkAssembler {
+    reassembler: Reassembler,
+}
+
+impl ChunkAssembler {
+    /// 使用指定的重组时限创建
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            reassembler: Reassembler::new(timeout),
+        }
+    }
+
+    /// 是否为分块消息
+    pub fn is_chunk(message: &MqttMessage) -> bool {
+        message.content_type.as_deref() == Some(CHUNK_CONTENT_TYPE)
+    }
+
+    /// 处理一条消息
+    pub fn handle(&mut self, message: MqttMessage) -> Option<MqttMessage> {
+        if !Self::is_chunk(&message) {
+            return Some(message);
+        }
+
+        // 顺便清理超时的传输
+        for transfer_id in self.reassembler.expire() {
+            log::warn!("⏰ Dropping incomplete chunked transfer: {}", transfer_id);
+        }
+
+        let chunk = match Chunk::from_slice(&message.payload) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                log::error!("Failed to parse chunk on topic {}: {}", message.topic, e);
+                return None;
+            }
+        };
+
+        match self.reassembler.push(chunk) {
+            Ok(Some(assembled)) => {
+                log::debug!(
+                    "📦 Reassembled {} bytes on topic {} (transfer {})",
+                    assembled.payload.len(),
+                    message.topic,
+                    assembled.transfer_id
+                );
+                Some(MqttMessage {
+                    id: assembled.transfer_id,
+                    payload: assembled.payload,
+                    content_type: assembled.content_type,
+                    ..message
+                })
+            }
+            Ok(None) => None,
+            Err(e) => {
+                log::error!(
+                    "Failed to reassemble chunk on topic {}: {}",
+                    message.topic,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// 丢弃超时未收齐的传输，返回其传输 ID
+    pub fn expire(&mut self) -> Vec<String> {
+        self.reassembler.expire()
+    }
+
+    /// 正在重组的传输数量
+    pub fn pending(&self) -> usize {
+        self.reassembler.pending()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_messages(payload: &[u8], content_type: &str) -> Vec<MqttMessage> {
+        Chunk::split(payload, 1024, Some(content_type))
+            .iter()
+            .map(|chunk| {
+                MqttMessage::new("cozy/large".to_string(), chunk.to_vec().unwrap(), 1)
+                    .with_content_type(CHUNK_CONTENT_TYPE)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reassemble_messages() {
+        let payload = vec![b'x'; 5000];
+        let mut assembler = ChunkAssembler::default();
+
+        let mut messages = chunk_messages(&payload, "application/json");
+        let last = messages.pop().unwrap();
+        for message in messages {
+            assert!(assembler.handle(message).is_none());
+        }
+        assert_eq!(assembler.pending(), 1);
+
+        let message = assembler.handle(last).unwrap();
+        assert_eq!(message.payload, payload);
+        assert_eq!(message.content_type.as_deref(), Some("application/json"));
+        assert_eq!(message.topic, "cozy/large");
+        assert_eq!(assembler.pending(), 0);
+    }
+
+    /// 按 rumqttd 的方式检查编码后的报文是否超过上限
+    fn fits(topic: &str, content_type: &str, qos: QoS, payload: Vec<u8>, max: usize) -> bool {
+        let properties = PublishProperties {
+            content_type: Some(content_type.to_string()),
+            ..Default::default()
+        };
+        let mut publish = Publish::new(topic, qos, payload, Some(properties));
+        publish.pkid = 1;
+        let mut buffer = bytes::BytesMut::new();
+        publish.write(&mut buffer).unwrap();
+        rumqttc::v5::mqttbytes::v5::check(buffer.iter(), Some(max)).is_ok()
+    }
+
+    #[test]
+    fn test_direct_payload_counts_packet_overhead() {
+        let (topic, qos) = ("cozy/devices/livingroom/large", QoS::AtLeastOnce);
+        let direct = 20480 - packet_size(topic, "application/json", qos, 0);
+        assert_eq!(packet_size(topic, "application/json", qos, direct), 20480);
+        assert!(fits(
+            topic,
+            "application/json",
+            qos,
+            vec![b'x'; direct],
+            20480
+        ));
+        assert!(!fits(
+            topic,
+            "application/json",
+            qos,
+            vec![b'x'; direct + 1],
+            20480
+        ));
+    }
+
+    #[test]
+    fn test_large_payload_chunks_fit_limit() {
+        let payload = vec![b'x'; 100_000];
+        let topic = "cozy/devices/livingroom/large";
+        for qos in [QoS::AtMostOnce, QoS::AtLeastOnce] {
+            for max_payload_size in [512, 4096, 20480] {
+                let chunk_size = chunk_size_for(
+                    max_payload_size,
+                    topic,
+                    qos,
+                    payload.len(),
+                    "application/cbor",
+                )
+                .unwrap();
+                let chunks = Chunk::split(&payload, chunk_size, Some("application/cbor"));
+                for chunk in &chunks {
+                    let data = chunk.to_vec().unwrap();
+                    assert!(fits(topic, CHUNK_CONTENT_TYPE, qos, data, max_payload_size));
+                }
+            }
+        }
+        assert!(chunk_size_for(
+            64,
+            topic,
+            QoS::AtLeastOnce,
+            payload.len(),
+            "application/cbor"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_plain_message_passes_through() {
+        let mut assembler = ChunkAssembler::default();
+        let message = MqttMessage::new("cozy/test".to_string(), b"{}".to_vec(), 1);
+        assert_eq!(
+            assembler.handle(message.clone()).unwrap().payload,
+            message.payload
+        );
+
+        // 无法解析的分块消息被丢弃
+        let broken = message.with_content_type(CHUNK_CONTENT_TYPE);
+        assert!(assembler.handle(broken).is_none());
+    }
+
+    #[test]
+    fn test_incomplete_transfer_expires() {
+        let mut assembler = ChunkAssembler::new(Duration::ZERO);
+        let messages = chunk_messages(&[1u8; 3000], "application/cbor");
+        assert!(assembler.handle(messages[0].clone()).is_none());
+        assert_eq!(assembler.expire().len(), 1);
+        assert_eq!(assembler.pending(), 0);
+    }
+}
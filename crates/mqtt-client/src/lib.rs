@@ -1,16 +1,29 @@
+use message_models::{CodecError, Encoding, VersionedEnvelope};
+use rumqttc::v5::mqttbytes::v5::{Packet, PublishProperties};
 use rumqttc::v5::{AsyncClient, Event, MqttOptions};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::{mpsc};
+use tokio::sync::{mpsc, watch};
 use tokio::time::timeout;
 
+mod chunking;
+mod reconnect;
+
+pub use chunking::ChunkAssembler;
+pub use reconnect::{ConnectionState, ReconnectPolicy};
 // 重新导出 QoS 类型，方便使用
 pub use rumqttc::v5::mqttbytes::QoS;
 
 /// MQTT 客户端管理器
 pub struct MqttClient {
     pub client: Option<AsyncClient>,
-    is_connected: bool,
+    /// 连接状态，由事件循环更新
+    state: Arc<watch::Sender<ConnectionState>>,
+    /// 当前的订阅，重连后重新订阅
+    subscriptions: Arc<Mutex<BTreeMap<String, QoS>>>,
     message_sender: mpsc::UnboundedSender<MqttMessage>,
     config: ClientConfig,
 }
@@ -25,6 +38,9 @@ pub struct ClientConfig {
     pub password: Option<String>,
     pub keep_alive: u16,
     pub clean_session: bool,
+    /// 断线重连策略
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
 }
 
 impl ClientConfig {
@@ -63,6 +79,7 @@ impl ClientConfig {
             password: std::env::var("MQTT_PASSWORD").ok(),
             keep_alive,
             clean_session: true,
+            reconnect: ReconnectPolicy::default(),
         }
     }
 
@@ -81,10 +98,19 @@ impl ClientConfig {
             password: None,
             keep_alive,
             clean_session: true,
+            reconnect: ReconnectPolicy::default(),
         }
     }
 }
 
+impl ClientConfig {
+    /// 设置断线重连策略
+    pub fn with_reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+}
+
 /// MQTT 消息结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MqttMessage {
@@ -94,6 +120,9 @@ pub struct MqttMessage {
     pub qos: u8,
     pub retain: bool,
     pub timestamp: u64,
+    /// MQTT v5 content-type 属性（如 `application/cbor`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
 }
 
 impl MqttMessage {
@@ -108,9 +137,16 @@ impl MqttMessage {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            content_type: None,
         }
     }
 
+    /// 设置 content-type
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
     pub fn payload_as_string(&self) -> String {
         String::from_utf8_lossy(&self.payload).to_string()
     }
@@ -121,6 +157,21 @@ impl MqttMessage {
     {
         serde_json::from_slice(&self.payload)
     }
+
+    /// 负载的编码：优先使用 content-type，缺失时按负载首字节识别
+    pub fn encoding(&self) -> Result<Encoding, CodecError> {
+        Encoding::resolve(self.content_type.as_deref(), &self.payload)
+    }
+
+    /// 按负载的编码自动解码
+    pub fn payload_decoded<T: DeserializeOwned>(&self) -> Result<T, CodecError> {
+        self.encoding()?.decode(&self.payload)
+    }
+
+    /// 按负载的编码自动解码为消息信封（自动检测 schema 版本）
+    pub fn payload_as_envelope(&self) -> Result<VersionedEnvelope, CodecError> {
+        VersionedEnvelope::from_bytes(self.encoding()?, &self.payload)
+    }
 }
 
 impl MqttClient {
@@ -129,7 +180,8 @@ impl MqttClient {
         Self {
             client: None,
             config,
-            is_connected: false,
+            state: Arc::new(watch::channel(ConnectionState::Disconnected).0),
+            subscriptions: Arc::new(Mutex::new(BTreeMap::new())),
             message_sender: tx,
         }
     }
@@ -138,6 +190,9 @@ impl MqttClient {
     pub async fn connect(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         log::info!("🔗 Connecting to MQTT Broker: {:?}", self.config);
 
+        // 重连策略决定事件循环的退避方式，不合法时拒绝连接
+        self.config.reconnect.validate()?;
+
         // 创建MQTT选项
         let mut mqtt_options = MqttOptions::new(
             &self.config.client_id,
@@ -146,7 +201,7 @@ impl MqttClient {
         );
 
         mqtt_options.set_keep_alive(Duration::from_secs(self.config.keep_alive as u64));
-        mqtt_options.set_clean_session(self.config.clean_session);
+        mqtt_options.set_clean_start(self.config.clean_session);
 
         // 如果有用户名和密码，设置认证
         if let Some(username) = &self.config.username {
@@ -158,22 +213,41 @@ impl MqttClient {
         // 创建客户端和事件循环
         let (client, mut event_loop) = AsyncClient::new(mqtt_options, 10);
 
-        self.client = Some(client);
-        self.is_connected = true;
+        self.client = Some(client.clone());
+        self.state.send_replace(ConnectionState::Connecting);
 
         // 启动事件循环任务
         let sender = self.message_sender.clone();
-        let _client_id = self.config.client_id.clone();
+        let state = self.state.clone();
+        let subscriptions = self.subscriptions.clone();
+        let policy = self.config.reconnect.clone();
         tokio::spawn(async move {
+            // 连续失败的次数
+            let mut attempt = 0u32;
             loop {
+                // 主动断开或客户端被丢弃后停止
+                if *state.borrow() == ConnectionState::Disconnected {
+                    break;
+                }
                 match timeout(Duration::from_secs(1), event_loop.poll()).await {
                     Ok(Ok(notification)) => match notification {
+                        Event::Incoming(Packet::ConnAck(connack)) => {
+                            log::info!("✅ MQTT connection established");
+                            // broker 没有保留会话时，重连后需要重新订阅
+                            if attempt > 0 && !connack.session_present {
+                                resubscribe(&client, &subscriptions);
+                            }
+                            attempt = 0;
+                            state.send_replace(ConnectionState::Connected);
+                        }
                         Event::Incoming(packet) => {
                             log::debug!("📨 Received MQTT packet: {:?}", packet);
 
-                            if let rumqttc::v5::mqttbytes::v5::Packet::Publish(publish, _) =
-                                packet.as_ref()
-                            {
+                            if let Packet::Publish(publish) = packet {
+                                let content_type = publish
+                                    .properties
+                                    .as_ref()
+                                    .and_then(|properties| properties.content_type.clone());
                                 let message = MqttMessage {
                                     id: uuid::Uuid::new_v4().to_string(),
                                     topic: String::from_utf8_lossy(&publish.topic).to_string(),
@@ -184,6 +258,7 @@ impl MqttClient {
                                         .duration_since(std::time::UNIX_EPOCH)
                                         .unwrap_or_default()
                                         .as_secs(),
+                                    content_type,
                                 };
 
                                 if let Err(e) = sender.send(message) {
@@ -196,8 +271,35 @@ impl MqttClient {
                         }
                     },
                     Ok(Err(e)) => {
-                        log::error!("MQTT event loop error: {}", e);
-                        break;
+                        if *state.borrow() == ConnectionState::Disconnected {
+                            break;
+                        }
+                        attempt += 1;
+                        if !policy.allows(attempt) {
+                            log::error!("MQTT event loop error, giving up: {}", e);
+                            state.send_replace(ConnectionState::Failed {
+                                error: e.to_string(),
+                            });
+                            break;
+                        }
+                        let delay = policy.next_delay(attempt);
+                        log::warn!(
+                            "MQTT event loop error: {}, reconnecting in {:?} (attempt {})",
+                            e,
+                            delay,
+                            attempt
+                        );
+                        state.send_replace(ConnectionState::Reconnecting {
+                            attempt,
+                            delay_ms: delay.as_millis() as u64,
+                            error: e.to_string(),
+                        });
+                        // 等待期间主动断开时立即停止
+                        let mut stopped = state.subscribe();
+                        tokio::select! {
+                            _ = tokio::time::sleep(delay) => {}
+                            _ = stopped.wait_for(|s| *s == ConnectionState::Disconnected) => {}
+                        }
                     }
                     Err(_) => {
                         // 超时，继续循环
@@ -215,20 +317,41 @@ impl MqttClient {
     pub async fn disconnect(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         log::info!("🔌 Disconnecting from MQTT Broker...");
 
-        if let Some(client) = &self.client {
-            client.disconnect().await?;
-        }
-
-        self.is_connected = false;
-        self.client = None;
+        // 事件循环已经停止时发送 DISCONNECT 会失败，但状态仍然要复位
+        let result = match self.client.take() {
+            Some(client) => client.disconnect().await,
+            None => Ok(()),
+        };
+        self.state.send_replace(ConnectionState::Disconnected);
+        result?;
 
         log::info!("✅ Disconnected from MQTT Broker");
         Ok(())
     }
 
-    /// 检查连接状态
+    /// 检查连接状态（已收到 broker 的 ConnAck 且连接未断开）
     pub fn is_connected(&self) -> bool {
-        self.is_connected
+        self.state.borrow().is_connected()
+    }
+
+    /// 当前连接状态
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.borrow().clone()
+    }
+
+    /// 订阅连接状态变化
+    pub fn watch_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// 当前的订阅（重连后会自动重新订阅）
+    pub fn subscriptions(&self) -> Vec<(String, QoS)> {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(topic, qos)| (topic.clone(), *qos))
+            .collect()
     }
 
     /// 订阅主题
@@ -240,6 +363,10 @@ impl MqttClient {
         if let Some(client) = &self.client {
             log::info!("📡 Subscribing to topic: {}", topic);
             client.subscribe(topic, qos).await?;
+            self.subscriptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(topic.to_string(), qos);
             log::info!("✅ Subscribed to topic: {}", topic);
             Ok(())
         } else {
@@ -255,6 +382,10 @@ impl MqttClient {
         if let Some(client) = &self.client {
             log::info!("📡 Unsubscribing from topic: {}", topic);
             client.unsubscribe(topic).await?;
+            self.subscriptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(topic);
             log::info!("✅ Unsubscribed from topic: {}", topic);
             Ok(())
         } else {
@@ -286,7 +417,37 @@ impl MqttClient {
         }
     }
 
-    /// 发布JSON消息
+    /// 发布消息并设置 MQTT v5 content-type 属性
+    pub async fn publish_with_content_type(
+        &self,
+        topic: &str,
+        payload: &[u8],
+        content_type: &str,
+        qos: QoS,
+        retain: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(client) = &self.client {
+            log::debug!(
+                "📤 Publishing message to topic: {}, size: {} bytes, content-type: {}",
+                topic,
+                payload.len(),
+                content_type
+            );
+            let properties = PublishProperties {
+                content_type: Some(content_type.to_string()),
+                ..Default::default()
+            };
+            client
+                .publish_with_properties(topic, qos, retain, payload.to_vec(), properties)
+                .await?;
+            log::debug!("✅ Message published successfully");
+            Ok(())
+        } else {
+            Err("Client not connected".into())
+        }
+    }
+
+    /// 发布JSON消息（content-type 为 `application/json`）
     pub async fn publish_json<T: serde::Serialize>(
         &self,
         topic: &str,
@@ -294,8 +455,22 @@ impl MqttClient {
         qos: QoS,
         retain: bool,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let payload = serde_json::to_vec(data)?;
-        self.publish(topic, &payload, qos, retain).await
+        self.publish_encoded(topic, data, Encoding::Json, qos, retain)
+            .await
+    }
+
+    /// 按指定编码（JSON / CBOR / MessagePack）发布消息，并标记对应的 content-type
+    pub async fn publish_encoded<T: serde::Serialize>(
+        &self,
+        topic: &str,
+        data: &T,
+        encoding: Encoding,
+        qos: QoS,
+        retain: bool,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let payload = encoding.encode(data)?;
+        self.publish_with_content_type(topic, &payload, encoding.content_type(), qos, retain)
+            .await
     }
 
     /// 获取客户端信息
@@ -303,11 +478,40 @@ impl MqttClient {
         ClientInfo {
             client_id: self.config.client_id.clone(),
             broker_url: format!("{}:{}", self.config.broker_host, self.config.broker_port),
-            is_connected: self.is_connected,
+            is_connected: self.is_connected(),
         }
     }
 }
 
+impl Drop for MqttClient {
+    /// 停止事件循环
+    fn drop(&mut self) {
+        self.state.send_replace(ConnectionState::Disconnected);
+    }
+}
+
+/// 重新订阅所有主题（不等待发送，避免阻塞事件循环）
+fn resubscribe(client: &AsyncClient, subscriptions: &Mutex<BTreeMap<String, QoS>>) {
+    let topics: Vec<(String, QoS)> = subscriptions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|(topic, qos)| (topic.clone(), *qos))
+        .collect();
+    if topics.is_empty() {
+        return;
+    }
+    log::info!("📡 Resubscribing to {} topics", topics.len());
+    let client = client.clone();
+    tokio::spawn(async move {
+        for (topic, qos) in topics {
+            if let Err(e) = client.subscribe(topic.clone(), qos).await {
+                log::error!("Failed to resubscribe to {}: {}", topic, e);
+            }
+        }
+    });
+}
+
 /// 客户端信息
 #[derive(Debug, Clone, Serialize)]
 pub struct ClientInfo {
@@ -343,3 +547,145 @@ impl MessageHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use message_models::Envelope;
+
+    #[test]
+    fn test_payload_decoded_by_content_type() {
+        let envelope = Envelope::user("测试");
+        for encoding in Encoding::all() {
+            let payload = encoding.encode(&envelope).unwrap();
+            let message = MqttMessage::new("cozy/test".to_string(), payload, 1)
+                .with_content_type(encoding.content_type());
+
+            assert_eq!(message.encoding().unwrap(), encoding);
+            let decoded = message.payload_as_envelope().unwrap();
+            assert_eq!(decoded.as_v0(), Some(&envelope));
+        }
+    }
+
+    #[test]
+    fn test_reconnect_backoff_with_jitter() {
+        let policy = ReconnectPolicy::default();
+        assert!(policy.validate().is_ok());
+        // random = 0.5 时没有抖动
+        assert_eq!(policy.delay(1, 0.5), Duration::from_millis(500));
+        assert_eq!(policy.delay(2, 0.5), Duration::from_millis(1000));
+        assert_eq!(policy.delay(4, 0.5), Duration::from_millis(4000));
+        assert_eq!(policy.delay(30, 0.5), Duration::from_millis(30_000));
+        // ±20% 抖动，且不超过上限
+        assert_eq!(policy.delay(1, 0.0), Duration::from_millis(400));
+        assert_eq!(policy.delay(1, 0.999), Duration::from_millis(599));
+        assert_eq!(policy.delay(30, 0.999), Duration::from_millis(30_000));
+        for attempt in 1..10 {
+            let delay = policy.next_delay(attempt);
+            assert!(delay >= policy.delay(attempt, 0.0) && delay <= policy.delay(attempt, 1.0));
+        }
+
+        assert!(policy.allows(u32::MAX));
+        assert!(!ReconnectPolicy::disabled().allows(1));
+        let invalid = ReconnectPolicy {
+            jitter: 1.5,
+            ..ReconnectPolicy::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_connection_state_and_subscriptions() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        // 没有 broker 监听的端口
+        let config = ClientConfig::new("test".to_string(), "127.0.0.1".to_string(), 9, 60)
+            .with_reconnect(ReconnectPolicy {
+                initial_delay_ms: 10,
+                max_delay_ms: 10,
+                ..ReconnectPolicy::default()
+            });
+        let mut client = MqttClient::new(config, tx);
+        assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+
+        client.connect().await.unwrap();
+        // 调用 connect 后，收到 ConnAck 之前不算已连接
+        assert!(!client.is_connected());
+        client.subscribe("cozy/a", QoS::AtLeastOnce).await.unwrap();
+        client.subscribe("cozy/b", QoS::AtMostOnce).await.unwrap();
+        client.unsubscribe("cozy/a").await.unwrap();
+        assert_eq!(
+            client.subscriptions(),
+            vec![("cozy/b".to_string(), QoS::AtMostOnce)]
+        );
+
+        // 连接失败后按策略重连，次数递增
+        let mut state = client.watch_state();
+        let reconnecting = timeout(
+            Duration::from_secs(5),
+            state.wait_for(
+                |s| matches!(s, ConnectionState::Reconnecting { attempt, .. } if *attempt >= 2),
+            ),
+        )
+        .await
+        .expect("client should keep reconnecting")
+        .unwrap()
+        .clone();
+        assert!(!reconnecting.is_connected());
+
+        client.disconnect().await.ok();
+        assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+        assert!(!client.get_client_info().is_connected);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let config = ClientConfig::new("test".to_string(), "127.0.0.1".to_string(), 9, 60)
+            .with_reconnect(ReconnectPolicy::disabled());
+        let mut client = MqttClient::new(config, tx);
+        client.connect().await.unwrap();
+
+        let mut state = client.watch_state();
+        timeout(
+            Duration::from_secs(5),
+            state.wait_for(|s| matches!(s, ConnectionState::Failed { .. })),
+        )
+        .await
+        .expect("client should give up")
+        .unwrap();
+
+        // 事件循环已停止，主动断开后状态仍然复位
+        client.disconnect().await.ok();
+        assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_policy() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let config = ClientConfig::new("test".to_string(), "127.0.0.1".to_string(), 9, 60)
+            .with_reconnect(ReconnectPolicy {
+                initial_delay_ms: 1000,
+                max_delay_ms: 10,
+                ..ReconnectPolicy::default()
+            });
+        let mut client = MqttClient::new(config, tx);
+        assert!(client.connect().await.is_err());
+        assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+    }
+
+    #[test]
+    fn test_payload_without_content_type() {
+        let envelope = Envelope::user("测试");
+        let payload = Encoding::MessagePack.encode(&envelope).unwrap();
+        let message = MqttMessage::new("cozy/test".to_string(), payload, 1);
+
+        // 旧客户端不发送 content-type，按首字节识别
+        assert_eq!(message.encoding().unwrap(), Encoding::MessagePack);
+        let decoded: Envelope = message.payload_decoded().unwrap();
+        assert_eq!(decoded, envelope);
+
+        let unknown = MqttMessage::new("cozy/test".to_string(), b"{}".to_vec(), 1)
+            .with_content_type("text/plain");
+        assert!(unknown.payload_as_envelope().is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! 断线重连
+//!
+//! 事件循环出错后按 [`ReconnectPolicy`] 指数退避（带随机抖动）重连，重连成功且 broker
+//! 没有保留会话时重新订阅所有主题；连接状态通过 [`ConnectionState`] 对外通知。
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 重连策略
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectPolicy {
+    /// 第一次重连前的等待时间（毫秒）
+    pub initial_delay_ms: u64,
+    /// 等待时间上限（毫秒）
+    pub max_delay_ms: u64,
+    /// 每次失败后等待时间的倍数
+    pub multiplier: f64,
+    /// 随机抖动比例（0.2 表示 ±20%），避免多个客户端同时重连
+    pub jitter: f64,
+    /// 连续失败的最大重连次数，`None` 表示一直重连
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 500,
+            max_delay_ms: 30_000,
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// 不重连：事件循环出错后客户端停止工作
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// 第 `attempt` 次（从 1 开始）重连前的等待时间，`random` 取值 `[0, 1)`
+    pub fn delay(&self, attempt: u32, random: f64) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let base = (self.initial_delay_ms as f64 * self.multiplier.powi(exponent))
+            .min(self.max_delay_ms as f64);
+        let factor = 1.0 + self.jitter * (2.0 * random - 1.0);
+        let delay = (base * factor).clamp(0.0, self.max_delay_ms as f64);
+        Duration::from_millis(delay as u64)
+    }
+
+    /// 第 `attempt` 次重连前的等待时间（随机抖动）
+    pub fn next_delay(&self, attempt: u32) -> Duration {
+        self.delay(attempt, rand::random::<f64>())
+    }
+
+    /// 是否还可以进行第 `attempt` 次重连
+    pub fn allows(&self, attempt: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempt <= max)
+    }
+
+    /// 验证参数
+    pub fn validate(&self) -> Result<(), String> {
+        if self.multiplier < 1.0 {
+            return Err(format!("multiplier must be >= 1, got {}", self.multiplier));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(format!("jitter must be within 0..=1, got {}", self.jitter));
+        }
+        if self.initial_delay_ms > self.max_delay_ms {
+            return Err(format!(
+                "initial_delay_ms ({}) must not exceed max_delay_ms ({})",
+                self.initial_delay_ms, self.max_delay_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 连接状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// 未连接（尚未调用 `connect`，或已主动断开）
+    Disconnected,
+    /// 正在建立首次连接
+    Connecting,
+    /// 已连接（收到 ConnAck）
+    Connected,
+    /// 连接断开，`delay_ms` 后进行第 `attempt` 次重连
+    Reconnecting {
+        attempt: u32,
+        delay_ms: u64,
+        error: String,
+    },
+    /// 重连次数用尽，事件循环已停止
+    Failed { error: String },
+}
+
+impl ConnectionState {
+    /// 是否已连接
+    pub fn is_connected(&self) -> bool {
+        matches!(self, ConnectionState::Connected)
+    }
+}
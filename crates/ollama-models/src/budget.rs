@@ -0,0 +1,407 @@
+//! 上下文窗口与 token 预算
+//!
+//! 发送前用 [`estimate_tokens`] 近似估算，收到响应后用 Ollama 返回的实际 token 数
+//! （`context` 长度或 `prompt_eval_count + eval_count`）校正已用量。
+
+use crate::chat::{ChatMessage, ChatResponse, ChatRole};
+use crate::management::ShowResponse;
+use crate::models::OllamaResponse;
+use crate::options::GenerateOptions;
+
+/// 未设置 `num_ctx` 时 Ollama 使用的上下文窗口大小
+pub const DEFAULT_NUM_CTX: u32 = 4096;
+
+/// 每条 chat 消息的模板开销（角色标记等）的估算值
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+/// 近似估算文本的 token 数
+///
+/// CJK 等非 ASCII 字符按每字 1 个 token，ASCII 文本按每 4 个字符 1 个 token（向上取整），
+/// 与常见 BPE 分词器的结果通常相差不超过 20%
+pub fn estimate_tokens(text: &str) -> u32 {
+    let (ascii, other) = text.chars().fold((0u32, 0u32), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// 估算 chat 消息列表的 token 数（内容、思考过程、工具调用参数与每条消息的模板开销）
+pub fn estimate_messages(messages: &[ChatMessage]) -> u32 {
+    messages.iter().map(estimate_message).sum()
+}
+
+fn estimate_message(message: &ChatMessage) -> u32 {
+    let thinking = message.thinking.as_deref().map_or(0, estimate_tokens);
+    let tool_calls: u32 = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|call| {
+            let arguments = serde_json::Value::Object(call.function.arguments.clone());
+            estimate_tokens(&call.function.name) + estimate_tokens(&arguments.to_string())
+        })
+        .sum();
+    MESSAGE_OVERHEAD_TOKENS + estimate_tokens(&message.content) + thinking + tool_calls
+}
+
+/// 预算状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetStatus {
+    /// 余量充足
+    Ok,
+    /// 超过摘要阈值：应将较早的历史压缩为摘要
+    Summarize,
+    /// 超过裁剪阈值：必须丢弃部分历史，否则 Ollama 会截断提示词
+    Trim,
+}
+
+/// 单个会话的 token 预算
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBudget {
+    num_ctx: u32,
+    reserved_output: u32,
+    used: u32,
+    measured: bool,
+    summarize_ratio: f64,
+    trim_ratio: f64,
+}
+
+impl TokenBudget {
+    /// 创建预算，`num_ctx` 为上下文窗口大小
+    pub fn new(num_ctx: u32) -> Self {
+        Self {
+            num_ctx: num_ctx.max(1),
+            reserved_output: 0,
+            used: 0,
+            measured: false,
+            summarize_ratio: 0.75,
+            trim_ratio: 0.9,
+        }
+    }
+
+    /// 根据 `/api/show` 的结果与请求参数确定上下文窗口
+    ///
+    /// 优先使用请求中的 `num_ctx`，其次是 Modelfile 中的 `PARAMETER num_ctx`，
+    /// 否则为 [`DEFAULT_NUM_CTX`]；结果不超过模型支持的最大上下文长度
+    pub fn from_show(show: &ShowResponse, options: Option<&GenerateOptions>) -> Self {
+        let num_ctx = options
+            .and_then(|options| options.num_ctx)
+            .or_else(|| modelfile_num_ctx(&show.parameters))
+            .unwrap_or(DEFAULT_NUM_CTX);
+        let num_ctx = show
+            .context_length()
+            .map_or(num_ctx, |max| num_ctx.min(max));
+        let budget = Self::new(num_ctx);
+        match options.and_then(|options| options.num_predict) {
+            Some(num_predict) if num_predict > 0 => budget.with_reserved_output(num_predict as u32),
+            _ => budget,
+        }
+    }
+
+    /// 为模型输出预留的 token 数
+    pub fn with_reserved_output(mut self, tokens: u32) -> Self {
+        self.reserved_output = tokens;
+        self
+    }
+
+    /// 设置摘要与裁剪阈值（占可用窗口的比例，`0 < summarize <= trim <= 1`）
+    pub fn with_thresholds(mut self, summarize: f64, trim: f64) -> Self {
+        self.summarize_ratio = summarize;
+        self.trim_ratio = trim;
+        self
+    }
+
+    /// 验证阈值
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.summarize_ratio > 0.0
+            && self.summarize_ratio <= self.trim_ratio
+            && self.trim_ratio <= 1.0)
+        {
+            return Err(format!(
+                "Thresholds must satisfy 0 < summarize ({}) <= trim ({}) <= 1",
+                self.summarize_ratio, self.trim_ratio
+            ));
+        }
+        if self.reserved_output >= self.num_ctx {
+            return Err(format!(
+                "Reserved output ({}) must be smaller than num_ctx ({})",
+                self.reserved_output, self.num_ctx
+            ));
+        }
+        Ok(())
+    }
+
+    /// 上下文窗口大小
+    pub fn num_ctx(&self) -> u32 {
+        self.num_ctx
+    }
+
+    /// 可用于提示词的 token 数（窗口减去预留的输出）
+    pub fn available(&self) -> u32 {
+        self.num_ctx.saturating_sub(self.reserved_output)
+    }
+
+    /// 已使用的 token 数
+    pub fn used(&self) -> u32 {
+        self.used
+    }
+
+    /// 已用量是否来自 Ollama 返回的实际值（否则为估算值）
+    pub fn is_measured(&self) -> bool {
+        self.measured
+    }
+
+    /// 剩余可用的 token 数
+    pub fn remaining(&self) -> u32 {
+        self.available().saturating_sub(self.used)
+    }
+
+    /// 已使用的比例
+    pub fn usage_ratio(&self) -> f64 {
+        self.used as f64 / self.available().max(1) as f64
+    }
+
+    /// 当前状态
+    pub fn status(&self) -> BudgetStatus {
+        self.status_for(self.used)
+    }
+
+    /// 追加 `prompt` 后的状态
+    pub fn check(&self, prompt: &str) -> BudgetStatus {
+        self.status_for(self.used.saturating_add(estimate_tokens(prompt)))
+    }
+
+    fn status_for(&self, used: u32) -> BudgetStatus {
+        let ratio = used as f64 / self.available().max(1) as f64;
+        if ratio >= self.trim_ratio {
+            BudgetStatus::Trim
+        } else if ratio >= self.summarize_ratio {
+            BudgetStatus::Summarize
+        } else {
+            BudgetStatus::Ok
+        }
+    }
+
+    /// 用估算值设置已用量（如发送前的消息列表）
+    pub fn set_estimated(&mut self, tokens: u32) {
+        self.used = tokens;
+        self.measured = false;
+    }
+
+    /// 用 Ollama 返回的实际 token 数更新已用量
+    ///
+    /// `prompt_eval_count` 与 `eval_count` 之和即下一轮请求中历史部分的大小
+    pub fn record_usage(&mut self, prompt_eval_count: Option<u32>, eval_count: Option<u32>) {
+        if prompt_eval_count.is_none() && eval_count.is_none() {
+            return;
+        }
+        self.used = prompt_eval_count
+            .unwrap_or(0)
+            .saturating_add(eval_count.unwrap_or(0));
+        self.measured = true;
+    }
+
+    /// 根据 `/api/generate` 的最终响应更新已用量（优先使用 `context` 的长度）
+    pub fn record_response(&mut self, response: &OllamaResponse) {
+        match &response.context {
+            Some(context) => {
+                self.used = u32::try_from(context.len()).unwrap_or(u32::MAX);
+                self.measured = true;
+            }
+            None => self.record_usage(response.prompt_eval_count, response.eval_count),
+        }
+    }
+
+    /// 根据 `/api/chat` 的最终响应更新已用量
+    pub fn record_chat(&mut self, response: &ChatResponse) {
+        self.record_usage(response.prompt_eval_count, response.eval_count);
+    }
+
+    /// 清零（开始新会话时）
+    pub fn reset(&mut self) {
+        self.used = 0;
+        self.measured = false;
+    }
+
+    /// 从最早的非系统消息开始丢弃，直到估算值低于摘要阈值，返回丢弃的条数
+    ///
+    /// 系统消息与最后一条消息始终保留；带工具调用的 assistant 消息与紧随其后的工具结果一起丢弃，
+    /// 不会留下没有对应调用的工具结果。丢弃后已用量更新为剩余消息的估算值
+    pub fn trim(&mut self, messages: &mut Vec<ChatMessage>) -> usize {
+        let target = (self.available() as f64 * self.summarize_ratio) as u32;
+        let mut total = estimate_messages(messages);
+        let mut removed = 0;
+        while total > target {
+            let last = messages.len().saturating_sub(1);
+            let Some(start) = messages[..last]
+                .iter()
+                .position(|message| message.role != ChatRole::System)
+            else {
+                break;
+            };
+            let mut end = start + 1;
+            if messages[start].has_tool_calls() {
+                while end < messages.len() && messages[end].role == ChatRole::Tool {
+                    end += 1;
+                }
+            }
+            if end > last {
+                break;
+            }
+            for message in messages.drain(start..end) {
+                total -= estimate_message(&message);
+                removed += 1;
+            }
+        }
+        self.set_estimated(total);
+        removed
+    }
+}
+
+/// Modelfile 参数中的 `num_ctx`（`/api/show` 的 `parameters`，每行 `<名称> <值>`）
+fn modelfile_num_ctx(parameters: &str) -> Option<u32> {
+    parameters.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("num_ctx"), Some(value)) => value.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::ToolCall;
+    use serde_json::json;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Why is the sky blue?"), 5);
+        assert_eq!(estimate_tokens("天空为什么是蓝色的"), 9);
+        assert_eq!(estimate_tokens("hi 你好"), 3);
+
+        let messages = vec![
+            ChatMessage::system("You are helpful."),
+            ChatMessage::user("Why is the sky blue?"),
+        ];
+        assert_eq!(estimate_messages(&messages), 4 + 4 + 4 + 5);
+    }
+
+    #[test]
+    fn test_budget_from_show_and_feedback() {
+        let show: ShowResponse = serde_json::from_value(json!({
+            "parameters": "stop \"<|eot_id|>\"\nnum_ctx 8192",
+            "model_info": { "llama.context_length": 131072 }
+        }))
+        .unwrap();
+        assert_eq!(TokenBudget::from_show(&show, None).num_ctx(), 8192);
+        // 请求的 num_ctx 不超过模型上限
+        let options = GenerateOptions::new()
+            .with_num_ctx(262144)
+            .with_num_predict(1024);
+        let budget = TokenBudget::from_show(&show, Some(&options));
+        assert_eq!(budget.num_ctx(), 131072);
+        assert_eq!(budget.available(), 130048);
+        assert_eq!(
+            TokenBudget::from_show(&ShowResponse::default(), None).num_ctx(),
+            DEFAULT_NUM_CTX
+        );
+
+        let mut budget = TokenBudget::new(1000);
+        assert!(budget.validate().is_ok());
+        assert_eq!(budget.status(), BudgetStatus::Ok);
+        budget.record_usage(Some(600), Some(200));
+        assert!(budget.is_measured());
+        assert_eq!(budget.remaining(), 200);
+        assert_eq!(budget.status(), BudgetStatus::Summarize);
+        assert_eq!(budget.check(&"x".repeat(400)), BudgetStatus::Trim);
+
+        let response: OllamaResponse = serde_json::from_value(json!({
+            "response": "", "done": true, "context": [1, 2, 3], "eval_count": 500
+        }))
+        .unwrap();
+        budget.record_response(&response);
+        assert_eq!(budget.used(), 3);
+
+        budget.reset();
+        assert_eq!(budget.used(), 0);
+        assert!(TokenBudget::new(100)
+            .with_thresholds(0.9, 0.5)
+            .validate()
+            .is_err());
+        assert!(TokenBudget::new(100)
+            .with_reserved_output(100)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_trim_keeps_system_and_last_message() {
+        let mut messages = vec![ChatMessage::system("You are helpful.")];
+        for i in 0..10 {
+            messages.push(ChatMessage::user(format!(
+                "question {} {}",
+                i,
+                "x".repeat(36)
+            )));
+            messages.push(ChatMessage::assistant("y".repeat(40)));
+        }
+        messages.push(ChatMessage::user("latest"));
+
+        // 每条问答约 14 + 14 个 token
+        let mut budget = TokenBudget::new(200);
+        let removed = budget.trim(&mut messages);
+        assert!(removed > 0);
+        assert!(budget.used() <= 150);
+        assert_eq!(budget.used(), estimate_messages(&messages));
+        assert_eq!(messages.len(), 22 - removed);
+        assert_eq!(messages[0].role, ChatRole::System);
+        assert_eq!(messages.last().unwrap().content, "latest");
+
+        // 只剩系统消息与最后一条时不再丢弃
+        let mut tiny = TokenBudget::new(1);
+        let mut messages = vec![ChatMessage::system("s"), ChatMessage::user("u")];
+        assert_eq!(tiny.trim(&mut messages), 0);
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_trim_drops_tool_results_with_their_call() {
+        let call = ToolCall::new("get_weather", Default::default());
+        let mut messages = vec![
+            ChatMessage::system("You are helpful."),
+            ChatMessage::assistant("")
+                .with_tool_call(call.clone())
+                .with_tool_call(call),
+            ChatMessage::tool("get_weather", "x".repeat(200)),
+            ChatMessage::tool("get_weather", "y".repeat(200)),
+            ChatMessage::user("latest"),
+        ];
+        let mut budget = TokenBudget::new(100);
+        assert_eq!(budget.trim(&mut messages), 3);
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| m.role != ChatRole::Tool));
+
+        // 工具结果是最后一条消息时，调用与结果都保留
+        let mut messages = vec![
+            ChatMessage::assistant("")
+                .with_tool_call(ToolCall::new("get_weather", Default::default())),
+            ChatMessage::tool("get_weather", "x".repeat(400)),
+        ];
+        assert_eq!(TokenBudget::new(10).trim(&mut messages), 0);
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn test_record_usage_saturates() {
+        let mut budget = TokenBudget::new(4096);
+        budget.record_usage(Some(u32::MAX), Some(10));
+        assert_eq!(budget.used(), u32::MAX);
+    }
+}
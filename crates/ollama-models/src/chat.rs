@@ -0,0 +1,440 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use crate::models::{OllamaRequest, OllamaResponse};
+use crate::options::{Format, GenerateOptions, KeepAlive};
+use crate::performance::PerformanceStats;
+
+/// `/api/chat` 消息角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// `/api/chat` 消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ChatMessage {
+    /// 角色
+    pub role: ChatRole,
+    /// 消息内容
+    pub content: String,
+    /// 图片（base64 编码，多模态模型使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub images: Option<Vec<String>>,
+    /// 模型发起的工具调用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// 思考过程（如果模型支持）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub thinking: Option<String>,
+    /// 工具结果对应的工具名称（`role` 为 `tool` 时使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tool_name: Option<String>,
+}
+
+impl ChatMessage {
+    /// 创建消息
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            images: None,
+            tool_calls: None,
+            thinking: None,
+            tool_name: None,
+        }
+    }
+
+    /// 系统消息
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::System, content)
+    }
+
+    /// 用户消息
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::User, content)
+    }
+
+    /// 模型回复
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::Assistant, content)
+    }
+
+    /// 工具执行结果
+    pub fn tool(tool_name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_name: Some(tool_name.into()),
+            ..Self::new(ChatRole::Tool, content)
+        }
+    }
+
+    /// 附加图片（base64 编码）
+    pub fn with_image(mut self, image: impl Into<String>) -> Self {
+        self.images.get_or_insert_with(Vec::new).push(image.into());
+        self
+    }
+
+    /// 附加工具调用
+    pub fn with_tool_call(mut self, call: ToolCall) -> Self {
+        self.tool_calls.get_or_insert_with(Vec::new).push(call);
+        self
+    }
+
+    /// 检查是否有工具调用
+    pub fn has_tool_calls(&self) -> bool {
+        self.tool_calls
+            .as_ref()
+            .is_some_and(|calls| !calls.is_empty())
+    }
+}
+
+/// 工具类型（目前 Ollama 只支持函数）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum ToolType {
+    #[default]
+    Function,
+}
+
+/// 工具定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct Tool {
+    /// 工具类型
+    #[serde(rename = "type")]
+    #[ts(rename = "type")]
+    pub tool_type: ToolType,
+    /// 函数定义
+    pub function: ToolFunction,
+}
+
+impl Tool {
+    /// 创建函数工具，`parameters` 为参数的 JSON Schema
+    pub fn function(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: Value,
+    ) -> Self {
+        Self {
+            tool_type: ToolType::Function,
+            function: ToolFunction {
+                name: name.into(),
+                description: description.into(),
+                parameters,
+            },
+        }
+    }
+}
+
+/// 函数定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ToolFunction {
+    /// 函数名称
+    pub name: String,
+    /// 函数说明
+    #[serde(default)]
+    pub description: String,
+    /// 参数的 JSON Schema
+    pub parameters: Value,
+}
+
+/// 模型发起的工具调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ToolCall {
+    /// 调用的函数
+    pub function: ToolCallFunction,
+}
+
+impl ToolCall {
+    /// 创建工具调用
+    pub fn new(name: impl Into<String>, arguments: Map<String, Value>) -> Self {
+        Self {
+            function: ToolCallFunction {
+                name: name.into(),
+                arguments,
+                index: None,
+            },
+        }
+    }
+}
+
+/// 工具调用的函数与参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ToolCallFunction {
+    /// 函数名称
+    pub name: String,
+    /// 调用参数
+    #[serde(default)]
+    pub arguments: Map<String, Value>,
+    /// 同一条消息中的调用序号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub index: Option<u32>,
+}
+
+/// `/api/chat` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ChatRequest {
+    /// 模型名称
+    pub model: String,
+    /// 对话消息
+    pub messages: Vec<ChatMessage>,
+    /// 可供模型调用的工具
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tools: Option<Vec<Tool>>,
+    /// 输出格式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub format: Option<Format>,
+    /// 模型参数（temperature、num_ctx 等）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub options: Option<GenerateOptions>,
+    /// 是否流式输出（Ollama 默认流式）
+    #[serde(default = "default_stream")]
+    pub stream: bool,
+    /// 是否输出思考过程（如果模型支持）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub think: Option<bool>,
+    /// 模型保留时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub keep_alive: Option<KeepAlive>,
+}
+
+fn default_stream() -> bool {
+    true
+}
+
+impl ChatRequest {
+    /// 创建非流式请求
+    pub fn new(model: impl Into<String>, messages: Vec<ChatMessage>) -> Self {
+        Self {
+            model: model.into(),
+            messages,
+            tools: None,
+            format: None,
+            options: None,
+            stream: false,
+            think: None,
+            keep_alive: None,
+        }
+    }
+
+    /// 设置可用工具
+    pub fn with_tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// 设置输出格式
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// 设置模型参数
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// 设置是否流式输出
+    pub fn with_stream(mut self, stream: bool) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// 设置模型保留时间
+    pub fn with_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 转换为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+/// `/api/generate` 请求转换为 chat 请求：`system` 作为系统消息，`prompt` 作为用户消息
+///
+/// `/api/chat` 不支持 `context`、`template` 与 `raw`，这些字段会被丢弃。
+impl From<OllamaRequest> for ChatRequest {
+    fn from(request: OllamaRequest) -> Self {
+        let mut messages = Vec::new();
+        if let Some(system) = request.system {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(request.prompt));
+        Self {
+            format: request.format,
+            options: request.options,
+            keep_alive: request.keep_alive,
+            ..Self::new(request.model, messages).with_stream(request.stream)
+        }
+    }
+}
+
+/// `/api/chat` 响应（流式输出时为其中一块）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ChatResponse {
+    /// 模型名称
+    pub model: String,
+    /// 创建时间
+    pub created_at: String,
+    /// 模型回复
+    pub message: ChatMessage,
+    /// 是否完成
+    #[serde(default)]
+    pub done: bool,
+    /// 完成原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub done_reason: Option<String>,
+    /// 总处理时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub total_duration: Option<u64>,
+    /// 模型加载时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub load_duration: Option<u64>,
+    /// 提示词评估次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prompt_eval_count: Option<u32>,
+    /// 提示词评估时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub prompt_eval_duration: Option<u64>,
+    /// 生成评估次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub eval_count: Option<u32>,
+    /// 生成评估时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub eval_duration: Option<u64>,
+}
+
+impl ChatResponse {
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 转换为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// 获取性能统计信息
+    pub fn performance_stats(&self) -> PerformanceStats {
+        OllamaResponse::from(self.clone()).performance_stats()
+    }
+}
+
+/// chat 响应转换为 generate 响应：回复内容放入 `response`，`context` 为空
+impl From<ChatResponse> for OllamaResponse {
+    fn from(response: ChatResponse) -> Self {
+        Self {
+            response: response.message.content,
+            context: None,
+            done: response.done,
+            done_reason: response.done_reason,
+            model: Some(response.model),
+            created_at: Some(response.created_at),
+            thinking: response.message.thinking,
+            total_duration: response.total_duration,
+            load_duration: response.load_duration,
+            prompt_eval_count: response.prompt_eval_count,
+            prompt_eval_duration: response.prompt_eval_duration,
+            eval_count: response.eval_count,
+            eval_duration: response.eval_duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_builder() {
+        let request = ChatRequest::new("llama3.2", vec![ChatMessage::user("hi")])
+            .with_format(Format::Json)
+            .with_options(GenerateOptions::new().with_temperature(0.2))
+            .with_keep_alive(KeepAlive::Seconds(-1));
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "model": "llama3.2",
+                "messages": [{ "role": "user", "content": "hi" }],
+                "format": "json",
+                "options": { "temperature": 0.2 },
+                "stream": false,
+                "keep_alive": -1
+            })
+        );
+    }
+
+    #[test]
+    fn test_conversions_from_generate_types() {
+        let request = OllamaRequest::new("qwen2.5", "你好")
+            .with_system("你是助手")
+            .with_context(Some(vec![1, 2, 3]))
+            .with_format(Format::Json)
+            .with_options(GenerateOptions::new().with_seed(7));
+        let chat = ChatRequest::from(request);
+        assert_eq!(
+            chat.messages,
+            vec![ChatMessage::system("你是助手"), ChatMessage::user("你好")]
+        );
+        assert_eq!(chat.format, Some(Format::Json));
+        assert_eq!(chat.options.unwrap().seed, Some(7));
+        assert!(!chat.stream);
+
+        let mut message = ChatMessage::assistant("你好！");
+        message.thinking = Some("打招呼".to_string());
+        let response = ChatResponse {
+            model: "qwen2.5".to_string(),
+            created_at: "2024-10-11T08:00:00Z".to_string(),
+            message,
+            done: true,
+            done_reason: Some("stop".to_string()),
+            total_duration: Some(2_000_000),
+            load_duration: None,
+            prompt_eval_count: Some(10),
+            prompt_eval_duration: None,
+            eval_count: Some(4),
+            eval_duration: Some(1_000_000),
+        };
+        assert_eq!(response.performance_stats().eval_count, Some(4));
+
+        let generate = OllamaResponse::from(response);
+        assert_eq!(generate.response, "你好！");
+        assert!(generate.has_thinking());
+        assert_eq!(generate.avg_eval_time_per_token_ms(), Some(0.25));
+    }
+}
@@ -0,0 +1,252 @@
+//! Ollama HTTP 客户端（`client` feature）
+//!
+//! 覆盖模型管理接口（版本、已安装模型、已加载模型、模型详情、拉取与删除）、向量接口与 token 预算。
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+
+use crate::budget::TokenBudget;
+use crate::embed::{check_dimensions, EmbedRequest, EmbedResponse};
+use crate::management::{
+    DeleteRequest, ModelList, PullProgress, PullRequest, RunningModelList, ShowRequest,
+    ShowResponse, VersionResponse,
+};
+use crate::options::GenerateOptions;
+use crate::stream::{StreamDecoder, StreamError};
+
+/// 客户端错误
+#[derive(Debug)]
+pub enum OllamaError {
+    /// 连接失败、超时或响应无法解析
+    Http(reqwest::Error),
+    /// Ollama 返回非 2xx 状态码
+    Api { status: u16, message: String },
+    /// 流式响应解码失败，或流中返回了错误
+    Stream(StreamError),
+    /// 响应内容不符合预期（如向量条数或维度不一致）
+    InvalidResponse(String),
+}
+
+impl fmt::Display for OllamaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OllamaError::Http(e) => write!(f, "HTTP error: {}", e),
+            OllamaError::Api { status, message } => {
+                write!(f, "Ollama API error ({}): {}", status, message)
+            }
+            OllamaError::Stream(e) => write!(f, "{}", e),
+            OllamaError::InvalidResponse(message) => write!(f, "Invalid response: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OllamaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OllamaError::Http(e) => Some(e),
+            OllamaError::Api { .. } | OllamaError::InvalidResponse(_) => None,
+            OllamaError::Stream(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for OllamaError {
+    fn from(e: reqwest::Error) -> Self {
+        OllamaError::Http(e)
+    }
+}
+
+impl From<StreamError> for OllamaError {
+    fn from(e: StreamError) -> Self {
+        OllamaError::Stream(e)
+    }
+}
+
+/// Ollama HTTP 客户端
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl OllamaClient {
+    /// 创建客户端，`base_url` 如 `http://127.0.0.1:11434`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// 从环境变量 `OLLAMA_HOST` / `OLLAMA_PORT` 创建客户端
+    pub fn from_env() -> Self {
+        let host = std::env::var("OLLAMA_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("OLLAMA_PORT").unwrap_or_else(|_| "11434".to_string());
+        Self::new(format!("http://{}:{}", host, port))
+    }
+
+    /// 使用自定义的 HTTP 客户端（超时、代理等）
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// 服务地址
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// 非 2xx 响应转换为错误（读取 `{"error": "..."}` 中的信息）
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, OllamaError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|value| value.get("error")?.as_str().map(str::to_string))
+            .unwrap_or(body);
+        Err(OllamaError::Api {
+            status: status.as_u16(),
+            message,
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, OllamaError> {
+        let response = self.http.get(self.url(path)).send().await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, OllamaError> {
+        let response = self.http.post(self.url(path)).json(body).send().await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    /// Ollama 版本（`GET /api/version`），可用作轻量的连通性检查
+    pub async fn version(&self) -> Result<String, OllamaError> {
+        let response: VersionResponse = self.get("/api/version").await?;
+        Ok(response.version)
+    }
+
+    /// 已安装的模型（`GET /api/tags`）
+    pub async fn list_models(&self) -> Result<ModelList, OllamaError> {
+        self.get("/api/tags").await
+    }
+
+    /// 已加载到内存的模型（`GET /api/ps`）
+    pub async fn running_models(&self) -> Result<RunningModelList, OllamaError> {
+        self.get("/api/ps").await
+    }
+
+    /// 模型详情（`POST /api/show`）
+    pub async fn show(&self, model: impl Into<String>) -> Result<ShowResponse, OllamaError> {
+        let request = ShowRequest {
+            model: model.into(),
+            verbose: None,
+        };
+        self.post("/api/show", &request).await
+    }
+
+    /// 按模型详情与请求参数创建 token 预算（见 [`TokenBudget::from_show`]）
+    pub async fn token_budget(
+        &self,
+        model: impl Into<String>,
+        options: Option<&GenerateOptions>,
+    ) -> Result<TokenBudget, OllamaError> {
+        let show = self.show(model).await?;
+        Ok(TokenBudget::from_show(&show, options))
+    }
+
+    /// 拉取模型（`POST /api/pull`），每收到一条进度调用一次 `on_progress`
+    pub async fn pull(
+        &self,
+        model: impl Into<String>,
+        mut on_progress: impl FnMut(&PullProgress),
+    ) -> Result<(), OllamaError> {
+        let request = PullRequest {
+            model: model.into(),
+            insecure: None,
+            stream: true,
+        };
+        let response = self
+            .http
+            .post(self.url("/api/pull"))
+            .json(&request)
+            .send()
+            .await?;
+        let mut response = Self::check(response).await?;
+
+        let mut decoder = StreamDecoder::<PullProgress>::new();
+        let mut success = false;
+        let mut handle = |progress: PullProgress| {
+            success = progress.is_success();
+            on_progress(&progress);
+        };
+        while let Some(bytes) = response.chunk().await? {
+            decoder.feed(&bytes)?.into_iter().for_each(&mut handle);
+        }
+        decoder.finish()?.into_iter().for_each(&mut handle);
+
+        if success {
+            Ok(())
+        } else {
+            Err(StreamError::Server("Pull ended before reporting success".to_string()).into())
+        }
+    }
+
+    /// 删除模型（`DELETE /api/delete`）
+    pub async fn delete(&self, model: impl Into<String>) -> Result<(), OllamaError> {
+        let request = DeleteRequest {
+            model: model.into(),
+        };
+        let response = self
+            .http
+            .delete(self.url("/api/delete"))
+            .json(&request)
+            .send()
+            .await?;
+        Self::check(response).await?;
+        Ok(())
+    }
+
+    /// 生成向量（`POST /api/embed`），校验返回的条数与维度
+    pub async fn embed(&self, request: &EmbedRequest) -> Result<EmbedResponse, OllamaError> {
+        let response: EmbedResponse = self.post("/api/embed", request).await?;
+        response
+            .validate(request.input.len())
+            .map_err(OllamaError::InvalidResponse)?;
+        if let Some(dimensions) = request.dimensions {
+            check_dimensions(&response.embeddings, Some(dimensions as usize))
+                .map_err(OllamaError::InvalidResponse)?;
+        }
+        Ok(response)
+    }
+
+    /// 分批生成向量，每批最多 `batch_size` 条，返回的向量与 `inputs` 顺序一致且维度相同
+    pub async fn embed_batch(
+        &self,
+        model: &str,
+        inputs: &[String],
+        batch_size: usize,
+    ) -> Result<Vec<Vec<f32>>, OllamaError> {
+        let mut embeddings = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(batch_size.max(1)) {
+            let request = EmbedRequest::new(model, batch.to_vec());
+            embeddings.extend(self.embed(&request).await?.embeddings);
+        }
+        // 不同批次之间的维度也必须一致
+        let dimension = embeddings.first().map(Vec::len);
+        check_dimensions(&embeddings, dimension).map_err(OllamaError::InvalidResponse)?;
+        Ok(embeddings)
+    }
+}
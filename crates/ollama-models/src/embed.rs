@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::options::{GenerateOptions, KeepAlive};
+
+/// 需要生成向量的文本：单条或多条
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(untagged)]
+#[ts(untagged)]
+pub enum EmbedInput {
+    Single(String),
+    Batch(Vec<String>),
+}
+
+impl EmbedInput {
+    /// 文本条数
+    pub fn len(&self) -> usize {
+        match self {
+            EmbedInput::Single(_) => 1,
+            EmbedInput::Batch(inputs) => inputs.len(),
+        }
+    }
+
+    /// 是否没有文本
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<&str> for EmbedInput {
+    fn from(input: &str) -> Self {
+        EmbedInput::Single(input.to_string())
+    }
+}
+
+impl From<String> for EmbedInput {
+    fn from(input: String) -> Self {
+        EmbedInput::Single(input)
+    }
+}
+
+impl From<Vec<String>> for EmbedInput {
+    fn from(inputs: Vec<String>) -> Self {
+        EmbedInput::Batch(inputs)
+    }
+}
+
+/// `/api/embed` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct EmbedRequest {
+    /// 模型名称
+    pub model: String,
+    /// 文本
+    pub input: EmbedInput,
+    /// 超出上下文长度时是否截断（为 false 时返回错误）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub truncate: Option<bool>,
+    /// 输出向量的维度（模型支持时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dimensions: Option<u32>,
+    /// 模型参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub options: Option<GenerateOptions>,
+    /// 模型保留时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub keep_alive: Option<KeepAlive>,
+}
+
+impl EmbedRequest {
+    /// 创建请求
+    pub fn new(model: impl Into<String>, input: impl Into<EmbedInput>) -> Self {
+        Self {
+            model: model.into(),
+            input: input.into(),
+            truncate: None,
+            dimensions: None,
+            options: None,
+            keep_alive: None,
+        }
+    }
+
+    /// 设置是否截断
+    pub fn with_truncate(mut self, truncate: bool) -> Self {
+        self.truncate = Some(truncate);
+        self
+    }
+
+    /// 设置输出向量的维度
+    pub fn with_dimensions(mut self, dimensions: u32) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    /// 设置模型保留时间
+    pub fn with_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+}
+
+/// `/api/embed` 响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct EmbedResponse {
+    /// 模型名称
+    #[serde(default)]
+    pub model: String,
+    /// 向量，与输入顺序一致
+    pub embeddings: Vec<Vec<f32>>,
+    /// 总处理时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub total_duration: Option<u64>,
+    /// 模型加载时间（纳秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub load_duration: Option<u64>,
+    /// 输入的 token 数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prompt_eval_count: Option<u32>,
+}
+
+impl EmbedResponse {
+    /// 向量维度（没有向量时为 `None`）
+    pub fn dimension(&self) -> Option<usize> {
+        self.embeddings.first().map(Vec::len)
+    }
+
+    /// 验证向量条数与维度：条数等于 `expected_count`，且所有向量维度相同
+    pub fn validate(&self, expected_count: usize) -> Result<(), String> {
+        if self.embeddings.len() != expected_count {
+            return Err(format!(
+                "Expected {} embeddings but got {}",
+                expected_count,
+                self.embeddings.len()
+            ));
+        }
+        check_dimensions(&self.embeddings, self.dimension())
+    }
+}
+
+/// 检查所有向量的维度都等于 `expected`（为 `None` 时只要求非空）
+pub fn check_dimensions(embeddings: &[Vec<f32>], expected: Option<usize>) -> Result<(), String> {
+    for (index, embedding) in embeddings.iter().enumerate() {
+        if embedding.is_empty() {
+            return Err(format!("Embedding {} is empty", index));
+        }
+        if let Some(expected) = expected.filter(|&expected| expected != embedding.len()) {
+            return Err(format!(
+                "Embedding {} has dimension {} but expected {}",
+                index,
+                embedding.len(),
+                expected
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 余弦相似度（维度不同或任一向量为零向量时为 `None`）
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() {
+        return None;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+    Some(dot / (norm_a * norm_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_embed_request_serialization() {
+        let single = EmbedRequest::new("all-minilm", "Why is the sky blue?");
+        assert_eq!(
+            serde_json::to_value(&single).unwrap(),
+            json!({ "model": "all-minilm", "input": "Why is the sky blue?" })
+        );
+
+        let batch = EmbedRequest::new("all-minilm", vec!["a".to_string(), "b".to_string()])
+            .with_truncate(false)
+            .with_dimensions(256);
+        assert_eq!(batch.input.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&batch).unwrap(),
+            json!({ "model": "all-minilm", "input": ["a", "b"], "truncate": false, "dimensions": 256 })
+        );
+    }
+
+    #[test]
+    fn test_embed_response_validation() {
+        let response: EmbedResponse = serde_json::from_str(
+            r#"{"model":"all-minilm","embeddings":[[0.1,0.2,0.3],[0.4,0.5,0.6]],"total_duration":14143917,"load_duration":1019500,"prompt_eval_count":8}"#,
+        )
+        .unwrap();
+        assert_eq!(response.dimension(), Some(3));
+        assert!(response.validate(2).is_ok());
+        assert!(response.validate(3).unwrap_err().contains("Expected 3"));
+
+        let ragged = vec![vec![0.1, 0.2], vec![0.3]];
+        assert!(check_dimensions(&ragged, Some(2))
+            .unwrap_err()
+            .contains("Embedding 1"));
+        assert!(check_dimensions(&[vec![]], None).is_err());
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), Some(0.0));
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), None);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), None);
+    }
+}
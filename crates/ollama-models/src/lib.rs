@@ -2,8 +2,29 @@
 //! 
 //! This crate provides data structures and utilities for working with Ollama API requests and responses.
 
+pub mod budget;
+pub mod chat;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod embed;
+pub mod management;
 pub mod models;
+pub mod openai;
+pub mod options;
 pub mod performance;
+pub mod stream;
+pub mod tools;
+pub mod typescript;
 
+pub use budget::*;
+pub use chat::*;
+#[cfg(feature = "client")]
+pub use client::*;
+pub use embed::*;
+pub use management::*;
 pub use models::*;
-pub use performance::*;
\ No newline at end of file
+pub use openai::*;
+pub use options::*;
+pub use performance::*;
+pub use stream::*;
+pub use tools::*;
\ No newline at end of file
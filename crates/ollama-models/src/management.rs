@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+/// 模型详情（`/api/tags`、`/api/ps`、`/api/show` 共用）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelDetails {
+    /// 基础模型
+    #[serde(default)]
+    pub parent_model: String,
+    /// 文件格式（如 `gguf`）
+    #[serde(default)]
+    pub format: String,
+    /// 模型家族（如 `llama`）
+    #[serde(default)]
+    pub family: String,
+    /// 包含的模型家族
+    #[serde(default)]
+    pub families: Option<Vec<String>>,
+    /// 参数规模（如 `8.0B`）
+    #[serde(default)]
+    pub parameter_size: String,
+    /// 量化等级（如 `Q4_K_M`）
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+/// 已安装的模型（`/api/tags`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelInfo {
+    /// 模型名称（如 `llama3.2:latest`）
+    pub name: String,
+    /// 模型标识
+    #[serde(default)]
+    pub model: String,
+    /// 修改时间
+    #[serde(default)]
+    pub modified_at: String,
+    /// 文件大小（字节）
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub size: u64,
+    /// 摘要
+    #[serde(default)]
+    pub digest: String,
+    /// 详情
+    #[serde(default)]
+    pub details: ModelDetails,
+}
+
+/// `/api/tags` 响应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelList {
+    /// 已安装的模型
+    #[serde(default)]
+    pub models: Vec<ModelInfo>,
+}
+
+impl ModelList {
+    /// 查找模型，未写标签时按 `latest` 匹配
+    pub fn find(&self, name: &str) -> Option<&ModelInfo> {
+        let normalized = normalize_model_name(name);
+        self.models
+            .iter()
+            .find(|model| normalize_model_name(&model.name) == normalized)
+    }
+
+    /// 模型是否已安装
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    /// 所有模型名称
+    pub fn names(&self) -> Vec<&str> {
+        self.models
+            .iter()
+            .map(|model| model.name.as_str())
+            .collect()
+    }
+}
+
+/// 补全模型名称中省略的 `:latest` 标签
+fn normalize_model_name(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("{}:latest", name)
+    }
+}
+
+/// 已加载到内存的模型（`/api/ps`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct RunningModel {
+    /// 模型名称
+    pub name: String,
+    /// 模型标识
+    #[serde(default)]
+    pub model: String,
+    /// 占用的内存（字节）
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub size: u64,
+    /// 摘要
+    #[serde(default)]
+    pub digest: String,
+    /// 详情
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// 卸载时间
+    #[serde(default)]
+    pub expires_at: String,
+    /// 占用的显存（字节）
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub size_vram: u64,
+    /// 加载时使用的上下文窗口大小
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub context_length: Option<u32>,
+}
+
+/// `/api/ps` 响应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct RunningModelList {
+    /// 已加载的模型
+    #[serde(default)]
+    pub models: Vec<RunningModel>,
+}
+
+/// `/api/show` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct ShowRequest {
+    /// 模型名称
+    pub model: String,
+    /// 是否返回完整的 tokenizer 等信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub verbose: Option<bool>,
+}
+
+/// `/api/show` 响应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ShowResponse {
+    /// Modelfile 内容
+    #[serde(default)]
+    pub modelfile: String,
+    /// 模型参数（Modelfile 中的 `PARAMETER`）
+    #[serde(default)]
+    pub parameters: String,
+    /// 提示词模板
+    #[serde(default)]
+    pub template: String,
+    /// 许可证
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub license: Option<String>,
+    /// 详情
+    #[serde(default)]
+    pub details: ModelDetails,
+    /// 模型元数据（如 `llama.context_length`）
+    #[serde(default)]
+    pub model_info: Map<String, Value>,
+    /// 模型能力（如 `completion`、`tools`、`vision`）
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// 修改时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub modified_at: Option<String>,
+}
+
+impl ShowResponse {
+    /// 模型支持的最大上下文长度（`model_info` 中的 `<family>.context_length`）
+    pub fn context_length(&self) -> Option<u32> {
+        self.model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+            .and_then(|length| u32::try_from(length).ok())
+    }
+
+    /// 是否具备某项能力
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
+
+/// `/api/pull` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct PullRequest {
+    /// 模型名称
+    pub model: String,
+    /// 是否允许不安全的连接（仅用于开发环境的私有仓库）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub insecure: Option<bool>,
+    /// 是否流式返回进度
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// `/api/pull` 进度（流式返回的每一块）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct PullProgress {
+    /// 状态（如 `pulling manifest`、`downloading`、`success`）
+    pub status: String,
+    /// 正在下载的层
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub digest: Option<String>,
+    /// 总字节数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub total: Option<u64>,
+    /// 已下载字节数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub completed: Option<u64>,
+}
+
+impl PullProgress {
+    /// 当前层的下载进度（0 到 1）
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.completed, self.total) {
+            (Some(completed), Some(total)) if total > 0 => {
+                Some((completed as f64 / total as f64).min(1.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// 是否已完成
+    pub fn is_success(&self) -> bool {
+        self.status == "success"
+    }
+}
+
+/// `/api/delete` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct DeleteRequest {
+    /// 模型名称
+    pub model: String,
+}
+
+/// `/api/version` 响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct VersionResponse {
+    /// Ollama 版本
+    pub version: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let json = r#"{
+  "models": [
+    {
+      "name": "llama3.2:latest",
+      "model": "llama3.2:latest",
+      "modified_at": "2024-10-11T08:00:00.000000+08:00",
+      "size": 2019393189,
+      "digest": "a80c4f17acd55265feec403c7aef86be0c25983ab279d83f3bcd3abbcb5b8b72",
+      "details": {
+        "parent_model": "",
+        "format": "gguf",
+        "family": "llama",
+        "families": ["llama"],
+        "parameter_size": "3.2B",
+        "quantization_level": "Q4_K_M"
+      }
+    },
+    { "name": "qwen2.5:7b", "size": 4683087332 }
+  ]
+}"#;
+        let list: ModelList = serde_json::from_str(json).unwrap();
+        assert_eq!(list.names(), vec!["llama3.2:latest", "qwen2.5:7b"]);
+        assert!(list.contains("llama3.2"));
+        assert!(list.contains("qwen2.5:7b"));
+        assert!(!list.contains("qwen2.5"));
+        assert_eq!(list.find("llama3.2").unwrap().details.family, "llama");
+    }
+
+    #[test]
+    fn test_show_response() {
+        let json = r#"{
+  "modelfile": "FROM llama3.2",
+  "parameters": "stop \"<|eot_id|>\"",
+  "template": "{{ .Prompt }}",
+  "details": { "format": "gguf", "family": "llama", "parameter_size": "3.2B" },
+  "model_info": {
+    "general.architecture": "llama",
+    "general.parameter_count": 3212749888,
+    "llama.context_length": 131072,
+    "llama.embedding_length": 3072
+  },
+  "capabilities": ["completion", "tools"]
+}"#;
+        let show: ShowResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(show.context_length(), Some(131072));
+        assert!(show.has_capability("tools"));
+        assert!(!show.has_capability("vision"));
+        assert_eq!(ShowResponse::default().context_length(), None);
+    }
+
+    #[test]
+    fn test_pull_progress() {
+        let progress: PullProgress = serde_json::from_str(
+            r#"{"status":"downloading","digest":"sha256:2ae6","total":2142590208,"completed":1071295104}"#,
+        )
+        .unwrap();
+        assert_eq!(progress.fraction(), Some(0.5));
+        assert!(!progress.is_success());
+
+        let done: PullProgress = serde_json::from_str(r#"{"status":"success"}"#).unwrap();
+        assert!(done.is_success());
+        assert_eq!(done.fraction(), None);
+    }
+}
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::options::{Format, GenerateOptions, KeepAlive};
 
 /// Ollama API 请求结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct OllamaRequest {
     /// 模型名称
     pub model: String,
@@ -11,16 +14,122 @@ pub struct OllamaRequest {
     pub stream: bool,
     /// 会话上下文（用于保持对话连续性）
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<Vec<f64>>")]
     pub context: Option<Vec<i64>>,
+    /// 系统提示词（覆盖模型自带的）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub system: Option<String>,
+    /// 提示词模板（覆盖模型自带的）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub template: Option<String>,
+    /// 是否跳过模板，直接把 `prompt` 交给模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub raw: Option<bool>,
+    /// 输出格式（`"json"` 或 JSON Schema）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub format: Option<Format>,
+    /// 模型参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub options: Option<GenerateOptions>,
+    /// 模型保留时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub keep_alive: Option<KeepAlive>,
+}
+
+impl OllamaRequest {
+    /// 创建非流式请求
+    pub fn new(model: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            prompt: prompt.into(),
+            stream: false,
+            context: None,
+            system: None,
+            template: None,
+            raw: None,
+            format: None,
+            options: None,
+            keep_alive: None,
+        }
+    }
+
+    /// 设置是否流式输出
+    pub fn with_stream(mut self, stream: bool) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// 设置会话上下文
+    pub fn with_context(mut self, context: Option<Vec<i64>>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// 设置系统提示词
+    pub fn with_system(mut self, system: impl Into<String>) -> Self {
+        self.system = Some(system.into());
+        self
+    }
+
+    /// 设置提示词模板
+    pub fn with_template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// 设置是否跳过模板
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+
+    /// 设置输出格式
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// 设置模型参数
+    pub fn with_options(mut self, options: GenerateOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// 设置模型保留时间
+    pub fn with_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// 验证请求
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("model must not be empty".to_string());
+        }
+        if self.raw == Some(true) && (self.system.is_some() || self.template.is_some()) {
+            return Err("system and template are ignored when raw is true".to_string());
+        }
+        match &self.options {
+            Some(options) => options.validate(),
+            None => Ok(()),
+        }
+    }
 }
 
 /// Ollama API 响应结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct OllamaResponse {
     /// 模型的回答
     pub response: String,
     /// 新的会话上下文
     #[serde(default)]
+    #[ts(as = "Option<Vec<f64>>")]
     pub context: Option<Vec<i64>>,
     /// 是否完成
     #[serde(default)]
@@ -39,21 +148,25 @@ pub struct OllamaResponse {
     pub thinking: Option<String>,
     /// 总处理时间（纳秒）
     #[serde(default)]
+    #[ts(as = "Option<f64>")]
     pub total_duration: Option<u64>,
     /// 模型加载时间（纳秒）
     #[serde(default)]
+    #[ts(as = "Option<f64>")]
     pub load_duration: Option<u64>,
     /// 提示词评估次数
     #[serde(default)]
     pub prompt_eval_count: Option<u32>,
     /// 提示词评估时间（纳秒）
     #[serde(default)]
+    #[ts(as = "Option<f64>")]
     pub prompt_eval_duration: Option<u64>,
     /// 生成评估次数
     #[serde(default)]
     pub eval_count: Option<u32>,
     /// 生成评估时间（纳秒）
     #[serde(default)]
+    #[ts(as = "Option<f64>")]
     pub eval_duration: Option<u64>,
 }
 
@@ -98,7 +211,9 @@ impl OllamaResponse {
             prompt_eval_count: self.prompt_eval_count,
             eval_count: self.eval_count,
             avg_eval_time_per_token_ms: self.avg_eval_time_per_token_ms(),
+            ..Default::default()
         }
+        .with_rates()
     }
 
     /// 检查是否有思考过程
@@ -0,0 +1,1023 @@
+//! OpenAI chat-completions 兼容层
+//!
+//! 与 Ollama chat / generate 类型互相转换，用于对接 llama.cpp、vLLM 等 OpenAI 兼容服务：
+//!
+//! - `temperature`、`top_p`、`seed`、`stop` 与 `max_tokens`（`num_predict`）一一对应，
+//!   其余模型参数（如 `top_k`、`num_ctx`）作为额外字段原样传递，反向转换时放回 `options`
+//! - `format` 对应 `response_format`（`json_object` / `json_schema`）
+//! - 图片按文件头识别类型后转换为 `data:` URL，思考过程对应 `reasoning_content`
+//! - 工具调用参数在 OpenAI 中是 JSON 字符串；Ollama 的工具调用没有 id，按顺序生成 `call_<n>`，
+//!   assistant 消息之后的第 n 条工具结果对应它的第 n 个调用
+//! - `usage.prompt_tokens` / `completion_tokens` 对应 `prompt_eval_count` / `eval_count`
+//! - generate 请求的 `raw`、`template` 与 `context` 作为额外字段原样传递，可以无损转换回来
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use ts_rs::TS;
+
+use crate::chat::{
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, Tool, ToolCall, ToolCallFunction,
+};
+use crate::models::{OllamaRequest, OllamaResponse};
+use crate::options::{Format, GenerateOptions, KeepAlive};
+
+/// OpenAI 消息角色（`developer` 等同于 `system`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum OpenAiRole {
+    System,
+    Developer,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// 消息内容：纯文本或多段内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(untagged)]
+#[ts(untagged)]
+pub enum OpenAiContent {
+    Text(String),
+    Parts(Vec<OpenAiContentPart>),
+}
+
+/// 多段内容中的一段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum OpenAiContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAiImageUrl },
+}
+
+/// 图片地址（Ollama 只支持 `data:` URL）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiImageUrl {
+    pub url: String,
+}
+
+/// OpenAI 工具调用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiToolCall {
+    /// 调用 id，工具结果通过它关联
+    pub id: String,
+    /// 固定为 `function`
+    #[serde(rename = "type")]
+    #[ts(rename = "type")]
+    pub call_type: String,
+    /// 调用的函数
+    pub function: OpenAiFunctionCall,
+}
+
+/// OpenAI 工具调用的函数与参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiFunctionCall {
+    /// 函数名称
+    pub name: String,
+    /// JSON 编码的调用参数
+    pub arguments: String,
+}
+
+/// OpenAI 消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiMessage {
+    /// 角色
+    pub role: OpenAiRole,
+    /// 内容（只有工具调用时可以为空）
+    #[serde(default)]
+    pub content: Option<OpenAiContent>,
+    /// 思考过程（vLLM、llama.cpp 等的扩展字段）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reasoning_content: Option<String>,
+    /// 模型发起的工具调用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tool_calls: Option<Vec<OpenAiToolCall>>,
+    /// 工具结果对应的调用 id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tool_call_id: Option<String>,
+    /// 工具结果对应的函数名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+}
+
+/// 停止词：单个或多个
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(untagged)]
+#[ts(untagged)]
+pub enum OpenAiStop {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<OpenAiStop> for Vec<String> {
+    fn from(stop: OpenAiStop) -> Self {
+        match stop {
+            OpenAiStop::One(stop) => vec![stop],
+            OpenAiStop::Many(stops) => stops,
+        }
+    }
+}
+
+/// 输出格式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum OpenAiResponseFormat {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: OpenAiJsonSchema },
+}
+
+/// 结构化输出的 JSON Schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiJsonSchema {
+    /// 名称
+    pub name: String,
+    /// JSON Schema
+    #[serde(default)]
+    pub schema: Value,
+    /// 是否严格遵守
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub strict: Option<bool>,
+}
+
+/// `/v1/chat/completions` 请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiChatRequest {
+    /// 模型名称
+    pub model: String,
+    /// 对话消息
+    pub messages: Vec<OpenAiMessage>,
+    /// 可供模型调用的工具（与 Ollama 格式相同）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tools: Option<Vec<Tool>>,
+    /// 输出格式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub response_format: Option<OpenAiResponseFormat>,
+    /// 采样温度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub temperature: Option<f64>,
+    /// 核采样阈值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub top_p: Option<f64>,
+    /// 最多生成的 token 数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_tokens: Option<i32>,
+    /// 随机种子
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub seed: Option<i64>,
+    /// 停止词
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stop: Option<OpenAiStop>,
+    /// 是否流式输出（默认否）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stream: Option<bool>,
+    /// 其他字段（服务端扩展的采样参数、`keep_alive` 等）
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub additional: HashMap<String, Value>,
+}
+
+/// token 用量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// 候选回复
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiChoice {
+    /// 序号
+    pub index: u32,
+    /// 回复消息
+    pub message: OpenAiMessage,
+    /// 结束原因（`stop`、`length`、`tool_calls`）
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+/// `/v1/chat/completions` 响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OpenAiChatResponse {
+    /// 响应 id
+    pub id: String,
+    /// 固定为 `chat.completion`
+    pub object: String,
+    /// 创建时间（Unix 秒）
+    #[ts(type = "number")]
+    pub created: i64,
+    /// 模型名称
+    pub model: String,
+    /// 候选回复
+    pub choices: Vec<OpenAiChoice>,
+    /// token 用量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub usage: Option<OpenAiUsage>,
+}
+
+impl OpenAiChatRequest {
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 转换为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+impl OpenAiChatResponse {
+    /// 从 JSON 字符串解析
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// 转换为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
+
+/// 按文件头识别 base64 图片的类型（PNG、JPEG、GIF、WebP），无法识别时按 JPEG 处理
+fn image_mime_type(image: &str) -> &'static str {
+    // 前 16 个 base64 字符对应 12 字节文件头
+    let head = image.trim_end_matches('=');
+    let head = head.get(..head.len().min(16) / 4 * 4).unwrap_or_default();
+    let bytes = STANDARD.decode(head).unwrap_or_default();
+    if bytes.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if bytes.starts_with(b"GIF8") {
+        "image/gif"
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP".as_slice()) {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    }
+}
+
+/// 生成工具调用 id（Ollama 的工具调用没有 id），并按位置把工具结果关联到调用
+#[derive(Default)]
+struct ToolCallIds {
+    next: usize,
+    /// 最近一条 assistant 消息中尚未得到结果的调用 id
+    pending: VecDeque<String>,
+}
+
+impl ToolCallIds {
+    fn next_id(&mut self) -> String {
+        let id = format!("call_{}", self.next);
+        self.next += 1;
+        id
+    }
+
+    /// 工具结果对应的调用 id；多出来的工具结果生成新的 id
+    fn result_id(&mut self) -> String {
+        match self.pending.pop_front() {
+            Some(id) => id,
+            None => self.next_id(),
+        }
+    }
+}
+
+fn to_openai_tool_call(call: &ToolCall, id: String) -> OpenAiToolCall {
+    OpenAiToolCall {
+        id,
+        call_type: "function".to_string(),
+        function: OpenAiFunctionCall {
+            name: call.function.name.clone(),
+            arguments: Value::Object(call.function.arguments.clone()).to_string(),
+        },
+    }
+}
+
+fn from_openai_tool_call(call: OpenAiToolCall, index: usize) -> Result<ToolCall, String> {
+    let arguments = if call.function.arguments.trim().is_empty() {
+        Map::new()
+    } else {
+        match serde_json::from_str(&call.function.arguments) {
+            Ok(Value::Object(arguments)) => arguments,
+            _ => {
+                return Err(format!(
+                    "Tool call '{}' arguments must be a JSON object",
+                    call.id
+                ))
+            }
+        }
+    };
+    Ok(ToolCall {
+        function: ToolCallFunction {
+            name: call.function.name,
+            arguments,
+            index: u32::try_from(index).ok(),
+        },
+    })
+}
+
+/// 转换单条消息，`call_ids` 记录前面 assistant 消息的工具调用
+fn to_openai_message(message: &ChatMessage, call_ids: &mut ToolCallIds) -> OpenAiMessage {
+    let role = match message.role {
+        ChatRole::System => OpenAiRole::System,
+        ChatRole::User => OpenAiRole::User,
+        ChatRole::Assistant => OpenAiRole::Assistant,
+        ChatRole::Tool => OpenAiRole::Tool,
+    };
+    let content = match &message.images {
+        Some(images) if !images.is_empty() => {
+            let mut parts = vec![OpenAiContentPart::Text {
+                text: message.content.clone(),
+            }];
+            parts.extend(images.iter().map(|image| OpenAiContentPart::ImageUrl {
+                image_url: OpenAiImageUrl {
+                    url: format!("data:{};base64,{}", image_mime_type(image), image),
+                },
+            }));
+            OpenAiContent::Parts(parts)
+        }
+        _ => OpenAiContent::Text(message.content.clone()),
+    };
+    let tool_call_id = match message.role {
+        ChatRole::Tool => Some(call_ids.result_id()),
+        _ => {
+            call_ids.pending.clear();
+            None
+        }
+    };
+    let tool_calls = message.tool_calls.as_ref().map(|calls| {
+        calls
+            .iter()
+            .map(|call| {
+                let id = call_ids.next_id();
+                call_ids.pending.push_back(id.clone());
+                to_openai_tool_call(call, id)
+            })
+            .collect()
+    });
+    OpenAiMessage {
+        role,
+        content: Some(content),
+        reasoning_content: message.thinking.clone(),
+        tool_calls,
+        tool_call_id,
+        name: message.tool_name.clone(),
+    }
+}
+
+/// 转换单条消息，`call_names` 记录调用 id 到函数名称
+fn from_openai_message(
+    message: OpenAiMessage,
+    call_names: &mut HashMap<String, String>,
+) -> Result<ChatMessage, String> {
+    let role = match message.role {
+        OpenAiRole::System | OpenAiRole::Developer => ChatRole::System,
+        OpenAiRole::User => ChatRole::User,
+        OpenAiRole::Assistant => ChatRole::Assistant,
+        OpenAiRole::Tool => ChatRole::Tool,
+    };
+    let mut text = String::new();
+    let mut images = Vec::new();
+    match message.content {
+        Some(OpenAiContent::Text(content)) => text = content,
+        Some(OpenAiContent::Parts(parts)) => {
+            for part in parts {
+                match part {
+                    OpenAiContentPart::Text { text: part } => text.push_str(&part),
+                    OpenAiContentPart::ImageUrl { image_url } => {
+                        let data = image_url
+                            .url
+                            .strip_prefix("data:")
+                            .and_then(|rest| rest.split_once(";base64,"))
+                            .map(|(_, data)| data.to_string())
+                            .ok_or_else(|| {
+                                "Only base64 data URLs are supported for images".to_string()
+                            })?;
+                        images.push(data);
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+    let tool_calls = match message.tool_calls {
+        Some(calls) => Some(
+            calls
+                .into_iter()
+                .enumerate()
+                .map(|(index, call)| {
+                    call_names.insert(call.id.clone(), call.function.name.clone());
+                    from_openai_tool_call(call, index)
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let tool_name = message.name.or_else(|| {
+        message
+            .tool_call_id
+            .as_ref()
+            .and_then(|id| call_names.get(id).cloned())
+    });
+    Ok(ChatMessage {
+        role,
+        content: text,
+        images: (!images.is_empty()).then_some(images),
+        tool_calls,
+        thinking: message.reasoning_content,
+        tool_name,
+    })
+}
+
+/// 只有 `/api/generate` 支持的字段，转换为 OpenAI 请求时作为额外字段传递
+const GENERATE_ONLY_FIELDS: [&str; 3] = ["raw", "template", "context"];
+
+/// 从额外字段中取出并解析一个字段
+fn take_field<T: DeserializeOwned>(
+    additional: &mut HashMap<String, Value>,
+    key: &str,
+) -> Result<Option<T>, String> {
+    additional
+        .remove(key)
+        .map(|value| serde_json::from_value(value).map_err(|e| format!("Invalid {}: {}", key, e)))
+        .transpose()
+}
+
+/// 有对应 OpenAI 字段的模型参数之外的参数，按原名称放入额外字段
+fn options_to_additional(options: GenerateOptions, additional: &mut HashMap<String, Value>) {
+    let GenerateOptions {
+        top_k,
+        num_ctx,
+        repeat_penalty,
+        additional: extra,
+        ..
+    } = options;
+    let mut insert = |key: &str, value: Option<Value>| {
+        if let Some(value) = value {
+            additional.insert(key.to_string(), value);
+        }
+    };
+    insert("top_k", top_k.map(Value::from));
+    insert("num_ctx", num_ctx.map(Value::from));
+    insert("repeat_penalty", repeat_penalty.map(Value::from));
+    additional.extend(extra);
+}
+
+impl From<ChatRequest> for OpenAiChatRequest {
+    fn from(request: ChatRequest) -> Self {
+        let mut call_ids = ToolCallIds::default();
+        let messages = request
+            .messages
+            .iter()
+            .map(|message| to_openai_message(message, &mut call_ids))
+            .collect();
+
+        let response_format = request.format.map(|format| match format {
+            Format::Json => OpenAiResponseFormat::JsonObject,
+            Format::Schema(schema) => OpenAiResponseFormat::JsonSchema {
+                json_schema: OpenAiJsonSchema {
+                    name: "response".to_string(),
+                    schema,
+                    strict: None,
+                },
+            },
+        });
+
+        let mut additional = HashMap::new();
+        if let Some(keep_alive) = request.keep_alive {
+            additional.insert(
+                "keep_alive".to_string(),
+                serde_json::to_value(keep_alive).unwrap_or(Value::Null),
+            );
+        }
+        if let Some(think) = request.think {
+            additional.insert("think".to_string(), Value::Bool(think));
+        }
+        let options = request.options.unwrap_or_default();
+        let (temperature, top_p, max_tokens, seed, stop) = (
+            options.temperature,
+            options.top_p,
+            options.num_predict,
+            options.seed,
+            options.stop.clone().map(OpenAiStop::Many),
+        );
+        options_to_additional(options, &mut additional);
+
+        Self {
+            model: request.model,
+            messages,
+            tools: request.tools,
+            response_format,
+            temperature,
+            top_p,
+            max_tokens,
+            seed,
+            stop,
+            stream: Some(request.stream),
+            additional,
+        }
+    }
+}
+
+impl TryFrom<OpenAiChatRequest> for ChatRequest {
+    type Error = String;
+
+    fn try_from(request: OpenAiChatRequest) -> Result<Self, Self::Error> {
+        if let Some(field) = GENERATE_ONLY_FIELDS
+            .iter()
+            .find(|field| request.additional.contains_key(**field))
+        {
+            return Err(format!("'{}' is only supported by /api/generate", field));
+        }
+        let mut call_names = HashMap::new();
+        let messages = request
+            .messages
+            .into_iter()
+            .map(|message| from_openai_message(message, &mut call_names))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let format = match request.response_format {
+            None | Some(OpenAiResponseFormat::Text) => None,
+            Some(OpenAiResponseFormat::JsonObject) => Some(Format::Json),
+            Some(OpenAiResponseFormat::JsonSchema { json_schema }) => {
+                Some(Format::Schema(json_schema.schema))
+            }
+        };
+
+        let mut additional = request.additional;
+        let keep_alive: Option<KeepAlive> = take_field(&mut additional, "keep_alive")?;
+        let think = additional.remove("think").and_then(|value| value.as_bool());
+
+        // 其余额外字段都视为模型参数
+        let mut options: GenerateOptions =
+            serde_json::from_value(Value::Object(additional.into_iter().collect()))
+                .map_err(|e| format!("Invalid options: {}", e))?;
+        options.temperature = request.temperature.or(options.temperature);
+        options.top_p = request.top_p.or(options.top_p);
+        options.num_predict = request.max_tokens.or(options.num_predict);
+        options.seed = request.seed.or(options.seed);
+        options.stop = request.stop.map(Vec::from).or(options.stop);
+
+        Ok(Self {
+            model: request.model,
+            messages,
+            tools: request.tools,
+            format,
+            options: (options != GenerateOptions::default()).then_some(options),
+            stream: request.stream.unwrap_or(false),
+            think,
+            keep_alive,
+        })
+    }
+}
+
+/// `/api/generate` 请求经由 [`ChatRequest`] 转换，`raw`、`template` 与 `context` 作为额外字段传递
+impl From<OllamaRequest> for OpenAiChatRequest {
+    fn from(mut request: OllamaRequest) -> Self {
+        let raw = request.raw.take().map(Value::Bool);
+        let template = request.template.take().map(Value::String);
+        let context = request
+            .context
+            .take()
+            .map(|context| Value::Array(context.into_iter().map(Value::from).collect()));
+        let mut openai = OpenAiChatRequest::from(ChatRequest::from(request));
+        for (key, value) in GENERATE_ONLY_FIELDS
+            .into_iter()
+            .zip([raw, template, context])
+        {
+            if let Some(value) = value {
+                openai.additional.insert(key.to_string(), value);
+            }
+        }
+        openai
+    }
+}
+
+/// 转换为 `/api/generate` 请求
+///
+/// 消息只能是一条纯文本用户消息（前面可以有一条系统消息），不支持工具与 `think`；
+/// `raw`、`template` 与 `context` 从额外字段还原
+impl TryFrom<OpenAiChatRequest> for OllamaRequest {
+    type Error = String;
+
+    fn try_from(mut request: OpenAiChatRequest) -> Result<Self, Self::Error> {
+        let raw = take_field(&mut request.additional, "raw")?;
+        let template = take_field(&mut request.additional, "template")?;
+        let context = take_field(&mut request.additional, "context")?;
+        let chat = ChatRequest::try_from(request)?;
+        if chat.tools.is_some() || chat.think.is_some() {
+            return Err("/api/generate does not support tools or think".to_string());
+        }
+
+        let mut messages = chat.messages.into_iter();
+        let (system, user) = match (messages.next(), messages.next(), messages.next()) {
+            (Some(user), None, None) => (None, user),
+            (Some(system), Some(user), None) if system.role == ChatRole::System => {
+                (Some(system.content), user)
+            }
+            _ => {
+                return Err(
+                    "/api/generate takes one user message, optionally after a system message"
+                        .to_string(),
+                )
+            }
+        };
+        if user.role != ChatRole::User || user.images.is_some() || user.tool_calls.is_some() {
+            return Err("/api/generate takes a plain-text user message".to_string());
+        }
+
+        Ok(Self {
+            model: chat.model,
+            prompt: user.content,
+            stream: chat.stream,
+            context,
+            system,
+            template,
+            raw,
+            format: chat.format,
+            options: chat.options,
+            keep_alive: chat.keep_alive,
+        })
+    }
+}
+
+impl From<ChatResponse> for OpenAiChatResponse {
+    fn from(response: ChatResponse) -> Self {
+        let created = DateTime::parse_from_rfc3339(&response.created_at)
+            .map(|time| time.timestamp())
+            .unwrap_or_default();
+        let usage = match (response.prompt_eval_count, response.eval_count) {
+            (None, None) => None,
+            (prompt, completion) => {
+                let (prompt, completion) = (prompt.unwrap_or(0), completion.unwrap_or(0));
+                Some(OpenAiUsage {
+                    prompt_tokens: prompt,
+                    completion_tokens: completion,
+                    total_tokens: prompt + completion,
+                })
+            }
+        };
+        let finish_reason = if response.message.has_tool_calls() {
+            Some("tool_calls".to_string())
+        } else {
+            response.done_reason.or_else(|| {
+                // 流式中间块没有结束原因
+                response.done.then(|| "stop".to_string())
+            })
+        };
+        let message = to_openai_message(&response.message, &mut ToolCallIds::default());
+        Self {
+            id: format!("chatcmpl-{}", created),
+            object: "chat.completion".to_string(),
+            created,
+            model: response.model,
+            choices: vec![OpenAiChoice {
+                index: 0,
+                message,
+                finish_reason,
+            }],
+            usage,
+        }
+    }
+}
+
+impl TryFrom<OpenAiChatResponse> for ChatResponse {
+    type Error = String;
+
+    /// 只转换第一个候选回复
+    fn try_from(response: OpenAiChatResponse) -> Result<Self, Self::Error> {
+        let choice = response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| "Response has no choices".to_string())?;
+        let message = from_openai_message(choice.message, &mut HashMap::new())?;
+        let created_at = DateTime::from_timestamp(response.created, 0)
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_default();
+        let done_reason = choice.finish_reason.map(|reason| match reason.as_str() {
+            // Ollama 调用工具时的结束原因为 stop
+            "tool_calls" => "stop".to_string(),
+            _ => reason,
+        });
+        Ok(Self {
+            model: response.model,
+            created_at,
+            message,
+            done: true,
+            done_reason,
+            total_duration: None,
+            load_duration: None,
+            prompt_eval_count: response.usage.as_ref().map(|usage| usage.prompt_tokens),
+            prompt_eval_duration: None,
+            eval_count: response.usage.as_ref().map(|usage| usage.completion_tokens),
+            eval_duration: None,
+        })
+    }
+}
+
+/// generate 响应转换为一条 assistant 回复
+impl From<OllamaResponse> for OpenAiChatResponse {
+    fn from(response: OllamaResponse) -> Self {
+        let mut message = ChatMessage::assistant(response.response);
+        message.thinking = response.thinking;
+        ChatResponse {
+            model: response.model.unwrap_or_default(),
+            created_at: response.created_at.unwrap_or_default(),
+            message,
+            done: response.done,
+            done_reason: response.done_reason,
+            total_duration: response.total_duration,
+            load_duration: response.load_duration,
+            prompt_eval_count: response.prompt_eval_count,
+            prompt_eval_duration: response.prompt_eval_duration,
+            eval_count: response.eval_count,
+            eval_duration: response.eval_duration,
+        }
+        .into()
+    }
+}
+
+/// 第一个候选回复转换为 generate 响应
+impl TryFrom<OpenAiChatResponse> for OllamaResponse {
+    type Error = String;
+
+    fn try_from(response: OpenAiChatResponse) -> Result<Self, Self::Error> {
+        ChatResponse::try_from(response).map(OllamaResponse::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_request_round_trip_through_openai() {
+        let mut arguments = Map::new();
+        arguments.insert("city".to_string(), json!("Paris"));
+        let request = ChatRequest::new(
+            "llama3.2",
+            vec![
+                ChatMessage::system("You are helpful."),
+                ChatMessage::user("What is in this image?").with_image("iVBORw0KGgoAAAANSUhEUg"),
+                ChatMessage::assistant("").with_tool_call(ToolCall::new("get_weather", arguments)),
+                ChatMessage::tool("get_weather", "22°C"),
+            ],
+        )
+        .with_tools(vec![Tool::function(
+            "get_weather",
+            "Get the weather",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )])
+        .with_format(Format::Json)
+        .with_options(
+            GenerateOptions::new()
+                .with_temperature(0.2)
+                .with_num_predict(128)
+                .with_num_ctx(8192)
+                .with_top_k(40)
+                .with_stop("\n\n"),
+        )
+        .with_keep_alive(KeepAlive::Duration("5m".to_string()));
+
+        let openai = OpenAiChatRequest::from(request.clone());
+        let value = serde_json::to_value(&openai).unwrap();
+        assert_eq!(value["max_tokens"], 128);
+        assert_eq!(value["num_ctx"], 8192);
+        assert_eq!(value["top_k"], 40);
+        assert_eq!(value["keep_alive"], "5m");
+        assert_eq!(value["response_format"], json!({ "type": "json_object" }));
+        assert_eq!(value["stream"], false);
+        assert_eq!(
+            value["messages"][1]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,iVBORw0KGgoAAAANSUhEUg"
+        );
+        let call = &value["messages"][2]["tool_calls"][0];
+        assert_eq!(call["id"], "call_0");
+        assert_eq!(call["function"]["arguments"], r#"{"city":"Paris"}"#);
+        assert_eq!(value["messages"][3]["tool_call_id"], "call_0");
+
+        // JSON 往返后再转换回来，除了工具调用序号外与原请求相同
+        let parsed = OpenAiChatRequest::from_json(&openai.to_json().unwrap()).unwrap();
+        let mut back = ChatRequest::try_from(parsed).unwrap();
+        back.messages[2].tool_calls.as_mut().unwrap()[0]
+            .function
+            .index = None;
+        assert_eq!(back, request);
+    }
+
+    #[test]
+    fn test_tool_results_match_calls_by_position() {
+        let call = |name: &str| ToolCall::new(name, Map::new());
+        let request = ChatRequest::new(
+            "llama3.2",
+            vec![
+                ChatMessage::assistant("")
+                    .with_tool_call(call("get_weather"))
+                    .with_tool_call(call("get_weather")),
+                ChatMessage::tool("get_weather", "22°C"),
+                ChatMessage::tool("get_weather", "18°C"),
+                ChatMessage::assistant("").with_tool_call(call("get_time")),
+                ChatMessage::tool("get_time", "12:00"),
+                // 没有对应调用的工具结果也要有 id
+                ChatMessage::tool("get_time", "13:00"),
+            ],
+        );
+        let value = serde_json::to_value(OpenAiChatRequest::from(request)).unwrap();
+        let messages = &value["messages"];
+        assert_eq!(messages[0]["tool_calls"][0]["id"], "call_0");
+        assert_eq!(messages[0]["tool_calls"][1]["id"], "call_1");
+        assert_eq!(messages[1]["tool_call_id"], "call_0");
+        assert_eq!(messages[2]["tool_call_id"], "call_1");
+        assert_eq!(messages[3]["tool_calls"][0]["id"], "call_2");
+        assert_eq!(messages[4]["tool_call_id"], "call_2");
+        assert_eq!(messages[5]["tool_call_id"], "call_3");
+        assert!(messages[0].get("tool_call_id").is_none());
+    }
+
+    #[test]
+    fn test_image_mime_type() {
+        assert_eq!(image_mime_type("iVBORw0KGgoAAAANSUhEUg"), "image/png");
+        assert_eq!(image_mime_type("/9j/4AAQSkZJRg=="), "image/jpeg");
+        assert_eq!(image_mime_type("R0lGODlhAQA="), "image/gif");
+        assert_eq!(image_mime_type("UklGRiQAAABXRUJQVlA4IA=="), "image/webp");
+        assert_eq!(image_mime_type("not base64!"), "image/jpeg");
+        assert_eq!(image_mime_type(""), "image/jpeg");
+        assert_eq!(image_mime_type("ééééééééé"), "image/jpeg");
+    }
+
+    #[test]
+    fn test_generate_request_round_trip_through_openai() {
+        let mut request = OllamaRequest::new("llama3.2", "[INST] hi [/INST]")
+            .with_system("You are helpful.")
+            .with_context(Some(vec![1, 2, 3]))
+            .with_format(Format::Json)
+            .with_options(GenerateOptions::new().with_temperature(0.2))
+            .with_stream(true);
+        request.raw = Some(true);
+        request.template = Some("{{ .Prompt }}".to_string());
+
+        let openai = OpenAiChatRequest::from(request.clone());
+        let value = serde_json::to_value(&openai).unwrap();
+        assert_eq!(value["raw"], true);
+        assert_eq!(value["template"], "{{ .Prompt }}");
+        assert_eq!(value["context"], json!([1, 2, 3]));
+
+        let parsed = OpenAiChatRequest::from_json(&openai.to_json().unwrap()).unwrap();
+        let back = OllamaRequest::try_from(parsed.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&request).unwrap()
+        );
+
+        // chat 请求不支持这些字段，不能悄悄丢弃
+        assert_eq!(
+            ChatRequest::try_from(parsed).unwrap_err(),
+            "'raw' is only supported by /api/generate"
+        );
+
+        let conversation = ChatRequest::new(
+            "llama3.2",
+            vec![ChatMessage::user("hi"), ChatMessage::assistant("hello")],
+        );
+        assert!(OllamaRequest::try_from(OpenAiChatRequest::from(conversation)).is_err());
+    }
+
+    #[test]
+    fn test_request_from_openai_json() {
+        let json = r#"{
+  "model": "qwen2.5",
+  "messages": [
+    { "role": "developer", "content": "Answer in JSON." },
+    { "role": "user", "content": [{ "type": "text", "text": "hi" }] },
+    { "role": "assistant", "content": null, "tool_calls": [
+      { "id": "abc", "type": "function", "function": { "name": "lookup", "arguments": "{\"q\":1}" } }
+    ] },
+    { "role": "tool", "tool_call_id": "abc", "content": "found" }
+  ],
+  "response_format": { "type": "json_schema", "json_schema": { "name": "answer", "schema": { "type": "object" }, "strict": true } },
+  "stop": "END",
+  "presence_penalty": 0.5
+}"#;
+        let request = ChatRequest::try_from(OpenAiChatRequest::from_json(json).unwrap()).unwrap();
+        assert_eq!(request.messages[0].role, ChatRole::System);
+        assert_eq!(request.messages[1].content, "hi");
+        assert_eq!(request.messages[2].content, "");
+        assert_eq!(request.messages[3].tool_name.as_deref(), Some("lookup"));
+        assert_eq!(
+            request.format,
+            Some(Format::Schema(json!({ "type": "object" })))
+        );
+        assert!(!request.stream);
+        let options = request.options.unwrap();
+        assert_eq!(options.stop, Some(vec!["END".to_string()]));
+        assert_eq!(options.additional["presence_penalty"], 0.5);
+
+        let remote_image = r#"{"model":"m","messages":[{"role":"user","content":[{"type":"image_url","image_url":{"url":"https://example.com/a.png"}}]}]}"#;
+        assert!(
+            ChatRequest::try_from(OpenAiChatRequest::from_json(remote_image).unwrap()).is_err()
+        );
+
+        let bad_arguments = r#"{"model":"m","messages":[{"role":"assistant","content":"","tool_calls":[{"id":"x","type":"function","function":{"name":"f","arguments":"[1]"}}]}]}"#;
+        assert!(
+            ChatRequest::try_from(OpenAiChatRequest::from_json(bad_arguments).unwrap()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_response_usage_mapping() {
+        let json = r#"{
+  "id": "chatcmpl-123",
+  "object": "chat.completion",
+  "created": 1728633600,
+  "model": "llama3.2",
+  "choices": [
+    { "index": 0, "message": { "role": "assistant", "content": "Hello!", "reasoning_content": "greet" }, "finish_reason": "stop" }
+  ],
+  "usage": { "prompt_tokens": 9, "completion_tokens": 12, "total_tokens": 21 }
+}"#;
+        let openai = OpenAiChatResponse::from_json(json).unwrap();
+        let chat = ChatResponse::try_from(openai.clone()).unwrap();
+        assert_eq!(chat.created_at, "2024-10-11T08:00:00Z");
+        assert_eq!(chat.message.thinking.as_deref(), Some("greet"));
+        assert_eq!(chat.prompt_eval_count, Some(9));
+        assert_eq!(chat.eval_count, Some(12));
+        assert!(chat.done);
+
+        let generate = OllamaResponse::try_from(openai).unwrap();
+        assert_eq!(generate.response, "Hello!");
+        assert_eq!(generate.eval_count, Some(12));
+
+        let back = OpenAiChatResponse::from(generate);
+        assert_eq!(back.created, 1728633600);
+        assert_eq!(back.choices[0].finish_reason.as_deref(), Some("stop"));
+        assert_eq!(
+            back.usage,
+            Some(OpenAiUsage {
+                prompt_tokens: 9,
+                completion_tokens: 12,
+                total_tokens: 21
+            })
+        );
+
+        let empty = OpenAiChatResponse {
+            choices: Vec::new(),
+            ..back
+        };
+        assert!(ChatResponse::try_from(empty).is_err());
+    }
+
+    #[test]
+    fn test_tool_call_response() {
+        let mut arguments = Map::new();
+        arguments.insert("city".to_string(), json!("Paris"));
+        let response = ChatResponse {
+            model: "llama3.2".to_string(),
+            created_at: "2024-07-22T20:33:28.123648Z".to_string(),
+            message: ChatMessage::assistant("")
+                .with_tool_call(ToolCall::new("get_weather", arguments)),
+            done: true,
+            done_reason: Some("stop".to_string()),
+            total_duration: None,
+            load_duration: None,
+            prompt_eval_count: None,
+            prompt_eval_duration: None,
+            eval_count: None,
+            eval_duration: None,
+        };
+        let openai = OpenAiChatResponse::from(response);
+        assert_eq!(
+            openai.choices[0].finish_reason.as_deref(),
+            Some("tool_calls")
+        );
+        assert!(openai.usage.is_none());
+
+        let back = ChatResponse::try_from(openai).unwrap();
+        assert_eq!(back.done_reason.as_deref(), Some("stop"));
+        assert_eq!(
+            back.message.tool_calls.unwrap()[0].function.arguments["city"],
+            "Paris"
+        );
+    }
+}
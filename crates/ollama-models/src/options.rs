@@ -0,0 +1,243 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// 模型参数（`/api/generate` 与 `/api/chat` 的 `options`）
+///
+/// 常用参数有类型，其余参数（如 `mirostat`、`num_gpu`）放在 `additional` 中原样传递。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct GenerateOptions {
+    /// 采样温度，越高越随机
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub temperature: Option<f64>,
+    /// 核采样概率阈值（0 到 1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub top_p: Option<f64>,
+    /// 只从概率最高的 k 个 token 中采样
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub top_k: Option<u32>,
+    /// 上下文窗口大小（token 数）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub num_ctx: Option<u32>,
+    /// 最多生成的 token 数（`-1` 不限制）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub num_predict: Option<i32>,
+    /// 随机种子，固定后相同输入得到相同输出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, as = "Option<f64>")]
+    pub seed: Option<i64>,
+    /// 停止词
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stop: Option<Vec<String>>,
+    /// 重复惩罚
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub repeat_penalty: Option<f64>,
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub additional: HashMap<String, Value>,
+}
+
+impl GenerateOptions {
+    /// 创建空参数（全部使用模型默认值）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置采样温度
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// 设置核采样阈值
+    pub fn with_top_p(mut self, top_p: f64) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// 设置 top_k
+    pub fn with_top_k(mut self, top_k: u32) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
+    /// 设置上下文窗口大小
+    pub fn with_num_ctx(mut self, num_ctx: u32) -> Self {
+        self.num_ctx = Some(num_ctx);
+        self
+    }
+
+    /// 设置最多生成的 token 数
+    pub fn with_num_predict(mut self, num_predict: i32) -> Self {
+        self.num_predict = Some(num_predict);
+        self
+    }
+
+    /// 设置随机种子
+    pub fn with_seed(mut self, seed: i64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// 添加停止词
+    pub fn with_stop(mut self, stop: impl Into<String>) -> Self {
+        self.stop.get_or_insert_with(Vec::new).push(stop.into());
+        self
+    }
+
+    /// 设置重复惩罚
+    pub fn with_repeat_penalty(mut self, repeat_penalty: f64) -> Self {
+        self.repeat_penalty = Some(repeat_penalty);
+        self
+    }
+
+    /// 设置其他参数
+    pub fn with_option(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.additional.insert(key.into(), value.into());
+        self
+    }
+
+    /// 验证参数取值
+    pub fn validate(&self) -> Result<(), String> {
+        if self.temperature.is_some_and(|t| t.is_nan() || t < 0.0) {
+            return Err("temperature must be non-negative".to_string());
+        }
+        if self.top_p.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+            return Err("top_p must be between 0 and 1".to_string());
+        }
+        if self.num_ctx == Some(0) {
+            return Err("num_ctx must be greater than 0".to_string());
+        }
+        if self.num_predict.is_some_and(|n| n < -2) {
+            return Err("num_predict must be -1, -2 or non-negative".to_string());
+        }
+        if self
+            .stop
+            .as_ref()
+            .is_some_and(|stop| stop.iter().any(String::is_empty))
+        {
+            return Err("stop sequences must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 输出格式：`"json"` 或 JSON Schema（结构化输出）
+#[derive(Debug, Clone, PartialEq, TS)]
+#[ts(type = "\"json\" | Record<string, unknown>")]
+pub enum Format {
+    /// 任意 JSON
+    Json,
+    /// 符合 JSON Schema 的 JSON
+    Schema(Value),
+}
+
+impl Format {
+    /// 约束输出符合 JSON Schema
+    pub fn schema(schema: Value) -> Self {
+        Format::Schema(schema)
+    }
+}
+
+impl Serialize for Format {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Format::Json => serializer.serialize_str("json"),
+            Format::Schema(schema) => schema.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Format {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(name) if name == "json" => Ok(Format::Json),
+            schema @ Value::Object(_) => Ok(Format::Schema(schema)),
+            other => Err(serde::de::Error::custom(format!(
+                "format must be \"json\" or a JSON schema object, got {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 模型在内存中保留的时间：时长字符串（如 `"5m"`）或秒数（`0` 立即卸载，负数一直保留）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(untagged)]
+#[ts(untagged)]
+pub enum KeepAlive {
+    Duration(String),
+    Seconds(#[ts(type = "number")] i64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_options_serialization() {
+        let options = GenerateOptions::new()
+            .with_temperature(0.2)
+            .with_num_ctx(8192)
+            .with_seed(42)
+            .with_stop("\n\n")
+            .with_option("mirostat", 2);
+
+        let value = serde_json::to_value(&options).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "temperature": 0.2,
+                "num_ctx": 8192,
+                "seed": 42,
+                "stop": ["\n\n"],
+                "mirostat": 2
+            })
+        );
+
+        let parsed: GenerateOptions = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, options);
+        assert_eq!(
+            serde_json::to_value(GenerateOptions::new()).unwrap(),
+            json!({})
+        );
+    }
+
+    #[test]
+    fn test_options_validation() {
+        assert!(GenerateOptions::new()
+            .with_temperature(0.0)
+            .with_top_p(1.0)
+            .with_num_predict(-1)
+            .validate()
+            .is_ok());
+        assert!(GenerateOptions::new()
+            .with_temperature(-0.1)
+            .validate()
+            .is_err());
+        assert!(GenerateOptions::new().with_top_p(1.5).validate().is_err());
+        assert!(GenerateOptions::new().with_num_ctx(0).validate().is_err());
+        assert!(GenerateOptions::new().with_stop("").validate().is_err());
+    }
+
+    #[test]
+    fn test_format_serialization() {
+        assert_eq!(serde_json::to_value(Format::Json).unwrap(), json!("json"));
+
+        let schema = json!({ "type": "object", "properties": { "age": { "type": "integer" } } });
+        let format: Format = serde_json::from_value(schema.clone()).unwrap();
+        assert_eq!(format, Format::schema(schema));
+
+        assert!(serde_json::from_value::<Format>(json!("yaml")).is_err());
+        assert!(serde_json::from_value::<Format>(json!(1)).is_err());
+    }
+}
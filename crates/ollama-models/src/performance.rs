@@ -1,5 +1,11 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use ts_rs::TS;
+
+use crate::models::OllamaResponse;
+
 /// 性能统计信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
 pub struct PerformanceStats {
     pub total_duration_ms: Option<f64>,
     pub load_duration_ms: Option<f64>,
@@ -8,9 +14,20 @@ pub struct PerformanceStats {
     pub prompt_eval_count: Option<u32>,
     pub eval_count: Option<u32>,
     pub avg_eval_time_per_token_ms: Option<f64>,
+    /// 生成速度（tokens/s）
+    pub tokens_per_second: Option<f64>,
+    /// 提示词评估速度（tokens/s）
+    pub prompt_tokens_per_second: Option<f64>,
 }
 
 impl PerformanceStats {
+    /// 由 token 数与耗时计算生成速度和提示词评估速度
+    pub fn with_rates(mut self) -> Self {
+        self.tokens_per_second = rate(self.eval_count, self.eval_duration_ms);
+        self.prompt_tokens_per_second = rate(self.prompt_eval_count, self.prompt_eval_duration_ms);
+        self
+    }
+
     /// 格式化性能统计信息为字符串
     pub fn format_summary(&self) -> String {
         let mut parts = Vec::new();
@@ -38,6 +55,10 @@ impl PerformanceStats {
         if let Some(avg) = self.avg_eval_time_per_token_ms {
             parts.push(format!("平均/token: {:.2}ms", avg));
         }
+
+        if let Some(speed) = self.tokens_per_second {
+            parts.push(format!("速度: {:.1} tokens/s", speed));
+        }
         
         parts.join(", ")
     }
@@ -75,7 +96,329 @@ impl PerformanceStats {
         if let Some(avg) = self.avg_eval_time_per_token_ms {
             report.push(format!("平均每token生成时间: {:.2}ms", avg));
         }
+
+        if let Some(speed) = self.prompt_tokens_per_second {
+            report.push(format!("提示词评估速度: {:.1} tokens/s", speed));
+        }
+
+        if let Some(speed) = self.tokens_per_second {
+            report.push(format!("生成速度: {:.1} tokens/s", speed));
+        }
         
         report.join("\n")
     }
 }
+
+/// 每秒 token 数
+fn rate(count: Option<u32>, duration_ms: Option<f64>) -> Option<f64> {
+    match (count, duration_ms) {
+        (Some(count), Some(ms)) if ms > 0.0 => Some(count as f64 * 1000.0 / ms),
+        _ => None,
+    }
+}
+
+/// 一组样本的分布
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct Distribution {
+    /// 样本数
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl Distribution {
+    /// 由样本计算分布（忽略 NaN，没有样本时为 `None`）
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.iter().copied().filter(|v| !v.is_nan()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        Some(Self {
+            count: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+        })
+    }
+}
+
+/// 最近秩法求百分位（`sorted` 已升序且非空）
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// 单个模型的汇总性能
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ModelPerformance {
+    /// 模型名称
+    pub model: String,
+    /// 请求数（全部请求）
+    pub count: usize,
+    /// 总处理时间（毫秒，最近的样本）
+    pub total_duration_ms: Option<Distribution>,
+    /// 模型加载时间（毫秒，最近的样本）
+    pub load_duration_ms: Option<Distribution>,
+    /// 最近每个请求的生成速度（tokens/s）
+    pub tokens_per_second: Option<Distribution>,
+    /// 提示词 token 总数
+    #[ts(type = "number")]
+    pub prompt_tokens: u64,
+    /// 生成 token 总数
+    #[ts(type = "number")]
+    pub eval_tokens: u64,
+    /// 整体生成速度：生成 token 总数 / 生成总时间（tokens/s）
+    pub throughput: Option<f64>,
+    /// 整体提示词评估速度（tokens/s）
+    pub prompt_throughput: Option<f64>,
+}
+
+/// 每个模型每项指标默认保留的样本数
+pub const DEFAULT_MAX_SAMPLES: usize = 1024;
+
+/// 固定容量的样本窗口，满了之后丢弃最早的样本
+#[derive(Debug, Clone, Default)]
+struct Window(VecDeque<f64>);
+
+impl Window {
+    fn push(&mut self, value: Option<f64>, capacity: usize) {
+        let Some(value) = value else { return };
+        if capacity == 0 {
+            return;
+        }
+        while self.0.len() >= capacity {
+            self.0.pop_front();
+        }
+        self.0.push_back(value);
+    }
+
+    fn distribution(&self) -> Option<Distribution> {
+        let (front, back) = self.0.as_slices();
+        Distribution::from_samples(&[front, back].concat())
+    }
+}
+
+/// 单个模型的原始样本
+#[derive(Debug, Clone, Default)]
+struct ModelSamples {
+    count: usize,
+    total_duration_ms: Window,
+    load_duration_ms: Window,
+    tokens_per_second: Window,
+    prompt_tokens: u64,
+    prompt_eval_ms: f64,
+    eval_tokens: u64,
+    eval_ms: f64,
+}
+
+/// 按模型累计多次请求的性能统计
+///
+/// 请求数、token 总数和整体速度按全部请求累计；延迟与速度分布只保留每个模型最近
+/// `max_samples` 个样本，长时间运行时内存占用不会随请求数增长。
+#[derive(Debug, Clone)]
+pub struct PerformanceAggregator {
+    models: BTreeMap<String, ModelSamples>,
+    max_samples: usize,
+}
+
+impl Default for PerformanceAggregator {
+    fn default() -> Self {
+        Self {
+            models: BTreeMap::new(),
+            max_samples: DEFAULT_MAX_SAMPLES,
+        }
+    }
+}
+
+impl PerformanceAggregator {
+    /// 创建聚合器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置每个模型每项指标保留的样本数
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// 每个模型每项指标保留的样本数
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+
+    /// 记录一次请求
+    pub fn record(&mut self, model: impl Into<String>, stats: &PerformanceStats) {
+        let capacity = self.max_samples;
+        let samples = self.models.entry(model.into()).or_default();
+        samples.count += 1;
+        samples.total_duration_ms.push(stats.total_duration_ms, capacity);
+        samples.load_duration_ms.push(stats.load_duration_ms, capacity);
+        samples.tokens_per_second.push(stats.tokens_per_second, capacity);
+        if let (Some(count), Some(ms)) = (stats.eval_count, stats.eval_duration_ms) {
+            samples.eval_tokens += count as u64;
+            samples.eval_ms += ms;
+        }
+        if let (Some(count), Some(ms)) = (stats.prompt_eval_count, stats.prompt_eval_duration_ms) {
+            samples.prompt_tokens += count as u64;
+            samples.prompt_eval_ms += ms;
+        }
+    }
+
+    /// 记录一次响应（没有模型名称时记为 `unknown`）
+    pub fn record_response(&mut self, response: &OllamaResponse) {
+        let model = response.model.as_deref().unwrap_or("unknown");
+        self.record(model, &response.performance_stats());
+    }
+
+    /// 已记录的模型名称
+    pub fn models(&self) -> Vec<&str> {
+        self.models.keys().map(String::as_str).collect()
+    }
+
+    /// 单个模型的汇总
+    pub fn summary(&self, model: &str) -> Option<ModelPerformance> {
+        let samples = self.models.get(model)?;
+        let throughput = |tokens: u64, ms: f64| (ms > 0.0).then(|| tokens as f64 * 1000.0 / ms);
+        Some(ModelPerformance {
+            model: model.to_string(),
+            count: samples.count,
+            total_duration_ms: samples.total_duration_ms.distribution(),
+            load_duration_ms: samples.load_duration_ms.distribution(),
+            tokens_per_second: samples.tokens_per_second.distribution(),
+            prompt_tokens: samples.prompt_tokens,
+            eval_tokens: samples.eval_tokens,
+            throughput: throughput(samples.eval_tokens, samples.eval_ms),
+            prompt_throughput: throughput(samples.prompt_tokens, samples.prompt_eval_ms),
+        })
+    }
+
+    /// 所有模型的汇总（按模型名称排序）
+    pub fn summaries(&self) -> Vec<ModelPerformance> {
+        self.models
+            .keys()
+            .filter_map(|model| self.summary(model))
+            .collect()
+    }
+
+    /// 清空所有样本
+    pub fn clear(&mut self) {
+        self.models.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(total_ms: f64, eval_count: u32, eval_ms: f64) -> PerformanceStats {
+        PerformanceStats {
+            total_duration_ms: Some(total_ms),
+            eval_count: Some(eval_count),
+            eval_duration_ms: Some(eval_ms),
+            prompt_eval_count: Some(10),
+            prompt_eval_duration_ms: Some(100.0),
+            ..Default::default()
+        }
+        .with_rates()
+    }
+
+    #[test]
+    fn test_tokens_per_second() {
+        let s = stats(2000.0, 50, 1000.0);
+        assert_eq!(s.tokens_per_second, Some(50.0));
+        assert_eq!(s.prompt_tokens_per_second, Some(100.0));
+        assert!(s.format_summary().contains("速度: 50.0 tokens/s"));
+        assert_eq!(PerformanceStats::default().with_rates().tokens_per_second, None);
+
+        let json = serde_json::to_value(&s).unwrap();
+        assert_eq!(json["tokens_per_second"], 50.0);
+        assert_eq!(json["prompt_tokens_per_second"], 100.0);
+        let parsed: PerformanceStats = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, s);
+    }
+
+    #[test]
+    fn test_response_stats_include_rates() {
+        let response: OllamaResponse = serde_json::from_str(
+            r#"{"response":"hi","done":true,"eval_count":20,"eval_duration":400000000}"#,
+        )
+        .unwrap();
+        assert_eq!(response.performance_stats().tokens_per_second, Some(50.0));
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let samples: Vec<f64> = (1..=100).rev().map(f64::from).collect();
+        let d = Distribution::from_samples(&samples).unwrap();
+        assert_eq!(d.count, 100);
+        assert_eq!(d.mean, 50.5);
+        assert_eq!((d.min, d.max), (1.0, 100.0));
+        assert_eq!((d.p50, d.p95, d.p99), (50.0, 95.0, 99.0));
+
+        let single = Distribution::from_samples(&[7.0]).unwrap();
+        assert_eq!((single.p50, single.p99), (7.0, 7.0));
+        assert!(Distribution::from_samples(&[]).is_none());
+        assert!(Distribution::from_samples(&[f64::NAN]).is_none());
+    }
+
+    #[test]
+    fn test_aggregator_per_model() {
+        let mut aggregator = PerformanceAggregator::new();
+        aggregator.record("llama3.2", &stats(1000.0, 100, 1000.0));
+        aggregator.record("llama3.2", &stats(3000.0, 100, 3000.0));
+        aggregator.record("qwen2.5", &PerformanceStats::default());
+
+        assert_eq!(aggregator.models(), vec!["llama3.2", "qwen2.5"]);
+
+        let llama = aggregator.summary("llama3.2").unwrap();
+        assert_eq!(llama.count, 2);
+        assert_eq!(llama.total_duration_ms.as_ref().unwrap().mean, 2000.0);
+        assert_eq!(llama.total_duration_ms.as_ref().unwrap().p99, 3000.0);
+        assert_eq!(llama.eval_tokens, 200);
+        // 整体速度按总量计算：200 tokens / 4s，而不是两次速度的平均值
+        assert_eq!(llama.throughput, Some(50.0));
+        assert_eq!(
+            llama.tokens_per_second.as_ref().unwrap().mean,
+            (100.0 + 100.0 / 3.0) / 2.0
+        );
+
+        let qwen = aggregator.summary("qwen2.5").unwrap();
+        assert_eq!(qwen.count, 1);
+        assert!(qwen.total_duration_ms.is_none() && qwen.throughput.is_none());
+
+        let summaries = aggregator.summaries();
+        assert_eq!(summaries.len(), 2);
+        let json = serde_json::to_value(&summaries).unwrap();
+        assert_eq!(json[0]["model"], "llama3.2");
+
+        aggregator.clear();
+        assert!(aggregator.summary("llama3.2").is_none());
+    }
+
+    #[test]
+    fn test_aggregator_keeps_recent_samples() {
+        let mut aggregator = PerformanceAggregator::new().with_max_samples(3);
+        assert_eq!(aggregator.max_samples(), 3);
+        for total in 1..=10 {
+            aggregator.record("llama3.2", &stats(f64::from(total), 10, 1000.0));
+        }
+
+        let llama = aggregator.summary("llama3.2").unwrap();
+        // 分布只包含最近 3 个样本，请求数与 token 总数仍按全部请求累计
+        let total = llama.total_duration_ms.unwrap();
+        assert_eq!(total.count, 3);
+        assert_eq!((total.min, total.max), (8.0, 10.0));
+        assert_eq!(llama.count, 10);
+        assert_eq!(llama.eval_tokens, 100);
+        assert_eq!(llama.throughput, Some(10.0));
+    }
+}
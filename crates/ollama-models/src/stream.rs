@@ -0,0 +1,261 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::models::OllamaResponse;
+use crate::performance::PerformanceStats;
+
+/// 流式响应解码错误
+#[derive(Debug)]
+pub enum StreamError {
+    /// 某一行不是合法的 JSON 或结构不匹配（`line` 从 1 开始）
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    /// Ollama 在流中返回的错误（`{"error": "..."}`）
+    Server(String),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Json { line, source } => write!(f, "Line {}: {}", line, source),
+            StreamError::Server(message) => write!(f, "Ollama error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Json { source, .. } => Some(source),
+            StreamError::Server(_) => None,
+        }
+    }
+}
+
+/// NDJSON 流式解码器
+///
+/// 与 IO 无关：按收到的顺序喂入字节块（可以在任意位置断开，包括多字节字符中间），
+/// 每遇到一个完整的行就解码出一块。默认解码为 [`OllamaResponse`]，
+/// `/api/chat` 使用 `StreamDecoder::<ChatResponse>::new()`。
+#[derive(Debug)]
+pub struct StreamDecoder<T = OllamaResponse> {
+    buffer: Vec<u8>,
+    line: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for StreamDecoder<T> {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            line: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> StreamDecoder<T> {
+    /// 创建解码器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 喂入字节块，返回其中完整行解码出的所有块
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<T>, StreamError> {
+        self.buffer.extend_from_slice(bytes);
+        let mut chunks = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            if let Some(chunk) = self.decode_line(&line)? {
+                chunks.push(chunk);
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// 流结束：解码末尾没有换行符的最后一行
+    pub fn finish(&mut self) -> Result<Option<T>, StreamError> {
+        let line = std::mem::take(&mut self.buffer);
+        self.decode_line(&line)
+    }
+
+    /// 尚未解码的字节数
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn decode_line(&mut self, line: &[u8]) -> Result<Option<T>, StreamError> {
+        self.line += 1;
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let json_error = |line, source| StreamError::Json { line, source };
+        let value: Value = serde_json::from_slice(line).map_err(|e| json_error(self.line, e))?;
+        if let Some(message) = value.get("error").and_then(Value::as_str) {
+            return Err(StreamError::Server(message.to_string()));
+        }
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| json_error(self.line, e))
+    }
+}
+
+/// 流式响应聚合器
+///
+/// 拼接每一块的 `response` 与 `thinking`，最后一块（`done` 为 true）携带上下文与性能数据。
+/// `/api/chat` 的块（[`ChatResponse`](crate::ChatResponse)）也可以直接传入，工具调用不会保留。
+#[derive(Debug, Clone, Default)]
+pub struct StreamAggregator {
+    response: String,
+    thinking: String,
+    last: Option<OllamaResponse>,
+    chunks: usize,
+}
+
+impl StreamAggregator {
+    /// 创建聚合器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入一块，返回是否已完成
+    pub fn push(&mut self, chunk: impl Into<OllamaResponse>) -> bool {
+        let mut chunk = chunk.into();
+        self.response.push_str(&chunk.response);
+        if let Some(thinking) = chunk.thinking.take() {
+            self.thinking.push_str(&thinking);
+        }
+        chunk.response.clear();
+        self.chunks += 1;
+        self.last = Some(chunk);
+        self.is_done()
+    }
+
+    /// 是否已收到最后一块
+    pub fn is_done(&self) -> bool {
+        self.last.as_ref().is_some_and(|chunk| chunk.done)
+    }
+
+    /// 目前为止的回复内容
+    pub fn text(&self) -> &str {
+        &self.response
+    }
+
+    /// 目前为止的思考过程
+    pub fn thinking(&self) -> Option<&str> {
+        (!self.thinking.is_empty()).then_some(self.thinking.as_str())
+    }
+
+    /// 已加入的块数
+    pub fn chunk_count(&self) -> usize {
+        self.chunks
+    }
+
+    /// 性能统计信息（完成后才有）
+    pub fn performance_stats(&self) -> Option<PerformanceStats> {
+        self.last
+            .as_ref()
+            .filter(|chunk| chunk.done)
+            .map(OllamaResponse::performance_stats)
+    }
+
+    /// 合并为完整的响应（未加入任何块时为 `None`）
+    pub fn finish(self) -> Option<OllamaResponse> {
+        let thinking = (!self.thinking.is_empty()).then_some(self.thinking);
+        self.last.map(|last| OllamaResponse {
+            response: self.response,
+            thinking,
+            ..last
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::ChatResponse;
+
+    const GENERATE_STREAM: &str = concat!(
+        r#"{"model":"llama3.2","created_at":"2023-08-04T08:52:19.385406455-07:00","response":"你","done":false}"#,
+        "\n",
+        r#"{"model":"llama3.2","created_at":"2023-08-04T08:52:19.385406455-07:00","response":"好","thinking":"greet","done":false}"#,
+        "\r\n\n",
+        r#"{"model":"llama3.2","created_at":"2023-08-04T19:22:45.499127Z","response":"！","done":true,"done_reason":"stop","context":[1,2,3],"total_duration":10706818083,"load_duration":6338219291,"prompt_eval_count":26,"prompt_eval_duration":130079000,"eval_count":259,"eval_duration":4232710000}"#,
+        "\n"
+    );
+
+    #[test]
+    fn test_decode_split_at_every_byte() {
+        let mut decoder = StreamDecoder::<OllamaResponse>::new();
+        let mut aggregator = StreamAggregator::new();
+        for byte in GENERATE_STREAM.as_bytes() {
+            for chunk in decoder.feed(std::slice::from_ref(byte)).unwrap() {
+                // 完成前没有性能统计
+                assert!(aggregator.performance_stats().is_none());
+                aggregator.push(chunk);
+            }
+        }
+        assert!(decoder.finish().unwrap().is_none());
+        assert_eq!(decoder.pending(), 0);
+
+        assert!(aggregator.is_done());
+        assert_eq!(aggregator.chunk_count(), 3);
+        assert_eq!(aggregator.text(), "你好！");
+        assert_eq!(aggregator.thinking(), Some("greet"));
+
+        let stats = aggregator.performance_stats().unwrap();
+        assert_eq!(stats.eval_count, Some(259));
+        assert_eq!(stats.prompt_eval_count, Some(26));
+
+        let response = aggregator.finish().unwrap();
+        assert_eq!(response.response, "你好！");
+        assert_eq!(response.context, Some(vec![1, 2, 3]));
+        assert_eq!(response.done_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_decode_last_line_without_newline_and_chat_chunks() {
+        let stream = concat!(
+            r#"{"model":"llama3.2","created_at":"2024-01-01T00:00:00Z","message":{"role":"assistant","content":"Hi"},"done":false}"#,
+            "\n",
+            r#"{"model":"llama3.2","created_at":"2024-01-01T00:00:01Z","message":{"role":"assistant","content":" there"},"done":true,"eval_count":2,"eval_duration":1000000}"#
+        );
+        let mut decoder = StreamDecoder::<ChatResponse>::new();
+        let mut chunks = decoder.feed(stream.as_bytes()).unwrap();
+        assert_eq!(chunks.len(), 1);
+        chunks.extend(decoder.finish().unwrap());
+
+        let mut aggregator = StreamAggregator::new();
+        let done = chunks.into_iter().map(|c| aggregator.push(c)).last();
+        assert_eq!(done, Some(true));
+        assert_eq!(aggregator.text(), "Hi there");
+        assert_eq!(
+            aggregator
+                .performance_stats()
+                .unwrap()
+                .avg_eval_time_per_token_ms,
+            Some(0.5)
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut decoder = StreamDecoder::<OllamaResponse>::new();
+        let err = decoder
+            .feed(b"{\"error\":\"model 'nope' not found\"}\n")
+            .unwrap_err();
+        assert!(matches!(err, StreamError::Server(ref m) if m.contains("not found")));
+
+        let mut decoder = StreamDecoder::<OllamaResponse>::new();
+        decoder.feed(b"\n").unwrap();
+        let err = decoder.feed(b"{not json}\n").unwrap_err();
+        assert!(err.to_string().starts_with("Line 2:"), "{}", err);
+
+        assert!(StreamAggregator::new().finish().is_none());
+    }
+}
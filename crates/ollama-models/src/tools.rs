@@ -0,0 +1,229 @@
+//! 工具调用的执行约定
+//!
+//! 模型在 `ChatResponse.message.tool_calls` 中返回结构化的调用，调用方通过 [`ToolExecutor`] 执行，
+//! 每个调用的结果（包括错误）作为 `tool` 消息追加到对话中，再发起下一轮请求：
+//!
+//! ```ignore
+//! let results = execute_tool_calls(&executor, &response.message).await;
+//! messages.push(response.message);
+//! messages.extend(results.into_iter().map(ChatMessage::from));
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+use std::future::Future;
+
+use crate::chat::{ChatMessage, Tool, ToolCall};
+
+/// 工具执行错误（会以 `{"error": "..."}` 的形式回传给模型）
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolError {
+    /// 没有该名称的工具
+    UnknownTool(String),
+    /// 参数缺失或不符合参数 schema
+    InvalidArguments { tool: String, message: String },
+    /// 执行失败
+    Failed { tool: String, message: String },
+    /// 调用被拒绝（如调用指向的模块未注册），内容为原因
+    Rejected(String),
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolError::UnknownTool(name) => write!(f, "Unknown tool: {}", name),
+            ToolError::InvalidArguments { tool, message } => {
+                write!(f, "Invalid arguments for '{}': {}", tool, message)
+            }
+            ToolError::Failed { tool, message } => {
+                write!(f, "Tool '{}' failed: {}", tool, message)
+            }
+            ToolError::Rejected(reason) => write!(f, "Tool call rejected: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+impl ToolCall {
+    /// 调用的函数名称
+    pub fn name(&self) -> &str {
+        &self.function.name
+    }
+
+    /// 将参数解析为具体类型
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, ToolError> {
+        serde_json::from_value(Value::Object(self.function.arguments.clone())).map_err(|e| {
+            ToolError::InvalidArguments {
+                tool: self.function.name.clone(),
+                message: e.to_string(),
+            }
+        })
+    }
+}
+
+/// 一次工具调用的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResult {
+    /// 工具名称
+    pub tool_name: String,
+    /// 执行结果，或执行错误
+    pub output: Result<Value, ToolError>,
+}
+
+impl ToolResult {
+    /// 是否执行成功
+    pub fn is_ok(&self) -> bool {
+        self.output.is_ok()
+    }
+
+    /// 回传给模型的内容：字符串结果原样返回，其余结果序列化为 JSON
+    pub fn content(&self) -> String {
+        match &self.output {
+            Ok(Value::String(text)) => text.clone(),
+            Ok(value) => value.to_string(),
+            Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+        }
+    }
+}
+
+impl From<ToolResult> for ChatMessage {
+    fn from(result: ToolResult) -> Self {
+        let content = result.content();
+        ChatMessage::tool(result.tool_name, content)
+    }
+}
+
+/// 工具执行器
+pub trait ToolExecutor {
+    /// 提供给模型的工具定义（`ChatRequest::with_tools`）
+    fn tools(&self) -> Vec<Tool>;
+
+    /// 执行一次调用，返回 JSON 结果（实现时可以直接写 `async fn`）
+    fn execute(&self, call: &ToolCall) -> impl Future<Output = Result<Value, ToolError>> + Send;
+}
+
+/// 按顺序执行消息中的所有工具调用
+///
+/// 不在 [`ToolExecutor::tools`] 中的调用不会执行，直接返回 [`ToolError::UnknownTool`]；
+/// 某个调用失败不影响其余调用
+pub async fn execute_tool_calls<E: ToolExecutor>(
+    executor: &E,
+    message: &ChatMessage,
+) -> Vec<ToolResult> {
+    let Some(calls) = &message.tool_calls else {
+        return Vec::new();
+    };
+    let tools = executor.tools();
+    let mut results = Vec::with_capacity(calls.len());
+    for call in calls {
+        let output = if tools.iter().any(|tool| tool.function.name == call.name()) {
+            executor.execute(call).await
+        } else {
+            Err(ToolError::UnknownTool(call.name().to_string()))
+        };
+        results.push(ToolResult {
+            tool_name: call.name().to_string(),
+            output,
+        });
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chat::ChatRole;
+    use serde::Deserialize;
+    use serde_json::{json, Map};
+
+    struct Calculator;
+
+    #[derive(Deserialize)]
+    struct AddArgs {
+        a: f64,
+        b: f64,
+    }
+
+    impl ToolExecutor for Calculator {
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool::function(
+                "add",
+                "Add two numbers",
+                json!({
+                    "type": "object",
+                    "required": ["a", "b"],
+                    "properties": { "a": { "type": "number" }, "b": { "type": "number" } }
+                }),
+            )]
+        }
+
+        async fn execute(&self, call: &ToolCall) -> Result<Value, ToolError> {
+            match call.name() {
+                "add" => {
+                    let args: AddArgs = call.parse_arguments()?;
+                    Ok(json!(args.a + args.b))
+                }
+                other => Err(ToolError::UnknownTool(other.to_string())),
+            }
+        }
+    }
+
+    fn call(name: &str, arguments: Value) -> ToolCall {
+        let Value::Object(arguments) = arguments else {
+            unreachable!()
+        };
+        ToolCall::new(name, arguments)
+    }
+
+    #[tokio::test]
+    async fn test_execute_tool_calls() {
+        let message = ChatMessage::assistant("")
+            .with_tool_call(call("add", json!({ "a": 1, "b": 2 })))
+            .with_tool_call(call("add", json!({ "a": 1 })))
+            .with_tool_call(call("rm", json!({ "path": "/" })));
+
+        let results = execute_tool_calls(&Calculator, &message).await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].output, Ok(json!(3.0)));
+        assert!(matches!(
+            results[1].output,
+            Err(ToolError::InvalidArguments { .. })
+        ));
+        assert_eq!(
+            results[2].output,
+            Err(ToolError::UnknownTool("rm".to_string()))
+        );
+
+        let messages: Vec<ChatMessage> = results.into_iter().map(ChatMessage::from).collect();
+        assert!(messages.iter().all(|m| m.role == ChatRole::Tool));
+        assert_eq!(messages[0].tool_name.as_deref(), Some("add"));
+        assert_eq!(messages[0].content, "3.0");
+        assert_eq!(messages[2].content, r#"{"error":"Unknown tool: rm"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_tool_result_content() {
+        let text = ToolResult {
+            tool_name: "weather".to_string(),
+            output: Ok(json!("22°C")),
+        };
+        assert!(text.is_ok());
+        assert_eq!(text.content(), "22°C");
+
+        let object = ToolResult {
+            tool_name: "weather".to_string(),
+            output: Ok(json!({ "celsius": 22 })),
+        };
+        assert_eq!(object.content(), r#"{"celsius":22}"#);
+
+        assert!(
+            execute_tool_calls(&Calculator, &ChatMessage::assistant("hi"))
+                .await
+                .is_empty()
+        );
+        let args: Map<String, Value> = call("add", json!({})).parse_arguments().unwrap();
+        assert!(args.is_empty());
+    }
+}
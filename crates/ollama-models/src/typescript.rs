@@ -0,0 +1,54 @@
+//! TypeScript 类型定义导出
+//!
+//! 前端使用的 Ollama 请求/响应类型由 [ts-rs](https://docs.rs/ts-rs) 生成，检入在
+//! [`BINDINGS_DIR`]。修改这些类型后需要重新生成：
+//!
+//! ```bash
+//! UPDATE_TS_BINDINGS=1 cargo test -p ollama-models typescript
+//! ```
+
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+use crate::chat::{ChatMessage, ChatRequest, ChatResponse};
+use crate::embed::{EmbedRequest, EmbedResponse};
+use crate::management::{ModelList, PullProgress, RunningModelList, ShowResponse, VersionResponse};
+use crate::models::{OllamaRequest, OllamaResponse};
+use crate::openai::{OpenAiChatRequest, OpenAiChatResponse};
+use crate::performance::{ModelPerformance, PerformanceStats};
+
+/// 检入的类型定义目录（相对于 crate 根目录）
+pub const BINDINGS_DIR: &str = "../../gui/frontend/src/types/generated/ollama-models";
+
+/// 将所有公开类型（及其依赖的类型）导出到 `out_dir`，每个类型一个 `.ts` 文件
+pub fn export_bindings(out_dir: impl AsRef<Path>) -> Result<(), ExportError> {
+    let out_dir = out_dir.as_ref();
+    OllamaRequest::export_all_to(out_dir)?;
+    OllamaResponse::export_all_to(out_dir)?;
+    ChatMessage::export_all_to(out_dir)?;
+    ChatRequest::export_all_to(out_dir)?;
+    ChatResponse::export_all_to(out_dir)?;
+    ModelList::export_all_to(out_dir)?;
+    RunningModelList::export_all_to(out_dir)?;
+    ShowResponse::export_all_to(out_dir)?;
+    PullProgress::export_all_to(out_dir)?;
+    VersionResponse::export_all_to(out_dir)?;
+    EmbedRequest::export_all_to(out_dir)?;
+    EmbedResponse::export_all_to(out_dir)?;
+    OpenAiChatRequest::export_all_to(out_dir)?;
+    OpenAiChatResponse::export_all_to(out_dir)?;
+    PerformanceStats::export_all_to(out_dir)?;
+    ModelPerformance::export_all_to(out_dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typescript_bindings_up_to_date() {
+        let checked_in = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_DIR);
+        ts_bindings::check_bindings("ollama-models", &checked_in, |dir| export_bindings(dir));
+    }
+}
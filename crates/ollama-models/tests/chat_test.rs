@@ -0,0 +1,217 @@
+use ollama_models::*;
+use serde_json::Value;
+
+/// 解析后再序列化，结果应与原始 JSON 相同
+fn assert_round_trip<T>(json: &str) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let parsed: T = serde_json::from_str(json).unwrap();
+    let original: Value = serde_json::from_str(json).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), original);
+    parsed
+}
+
+#[test]
+fn test_chat_request_with_history_and_images() {
+    let json = r#"{
+  "model": "llava",
+  "messages": [
+    { "role": "system", "content": "You are a helpful assistant." },
+    { "role": "user", "content": "why is the sky blue?" },
+    { "role": "assistant", "content": "due to rayleigh scattering." },
+    {
+      "role": "user",
+      "content": "what is in this image?",
+      "images": ["iVBORw0KGgoAAAANSUhEUgAAAG0AAABmCAYAAADBPx+VAAAACXBIWXMAAAsTAAALEwEAmpwYAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAA3VSURBVHgB7Z27r"]
+    }
+  ],
+  "stream": false,
+  "keep_alive": "5m"
+}"#;
+
+    let request: ChatRequest = assert_round_trip(json);
+    assert_eq!(request.messages.len(), 4);
+    assert_eq!(request.messages[0].role, ChatRole::System);
+    assert_eq!(request.messages[3].images.as_ref().unwrap().len(), 1);
+    assert_eq!(
+        request.keep_alive,
+        Some(KeepAlive::Duration("5m".to_string()))
+    );
+}
+
+#[test]
+fn test_chat_request_with_tools() {
+    let json = r#"{
+  "model": "llama3.2",
+  "messages": [
+    { "role": "user", "content": "What is the weather today in Paris?" }
+  ],
+  "tools": [
+    {
+      "type": "function",
+      "function": {
+        "name": "get_current_weather",
+        "description": "Get the current weather for a location",
+        "parameters": {
+          "type": "object",
+          "properties": {
+            "location": {
+              "type": "string",
+              "description": "The location to get the weather for, e.g. San Francisco, CA"
+            },
+            "format": {
+              "type": "string",
+              "description": "The format to return the weather in, e.g. 'celsius' or 'fahrenheit'",
+              "enum": ["celsius", "fahrenheit"]
+            }
+          },
+          "required": ["location", "format"]
+        }
+      }
+    }
+  ],
+  "stream": false
+}"#;
+
+    let request: ChatRequest = assert_round_trip(json);
+    let tools = request.tools.unwrap();
+    assert_eq!(tools[0].tool_type, ToolType::Function);
+    assert_eq!(tools[0].function.name, "get_current_weather");
+    assert_eq!(tools[0].function.parameters["required"][0], "location");
+}
+
+#[test]
+fn test_chat_request_with_schema_format_and_options() {
+    let json = r#"{
+  "model": "llama3.1",
+  "messages": [
+    { "role": "user", "content": "Ollama is 22 years old and busy saving the world. Return a JSON object with the age and availability." }
+  ],
+  "format": {
+    "type": "object",
+    "properties": {
+      "age": { "type": "integer" },
+      "available": { "type": "boolean" }
+    },
+    "required": ["age", "available"]
+  },
+  "options": {
+    "temperature": 0.7,
+    "seed": 101,
+    "num_ctx": 4096,
+    "stop": ["\n\n"]
+  },
+  "stream": false,
+  "think": false,
+  "keep_alive": 0
+}"#;
+
+    let request: ChatRequest = assert_round_trip(json);
+    match request.format {
+        Some(Format::Schema(schema)) => assert_eq!(schema["required"][1], "available"),
+        other => panic!("Expected schema format, got {:?}", other),
+    }
+    let options = request.options.unwrap();
+    assert_eq!(options.num_ctx, Some(4096));
+    assert_eq!(options.stop, Some(vec!["\n\n".to_string()]));
+    assert_eq!(request.keep_alive, Some(KeepAlive::Seconds(0)));
+}
+
+#[test]
+fn test_chat_request_defaults_to_streaming() {
+    let request = ChatRequest::from_json(
+        r#"{"model":"llama3.2","messages":[{"role":"user","content":"hi"}]}"#,
+    )
+    .unwrap();
+    assert!(request.stream);
+    assert!(request.tools.is_none());
+}
+
+#[test]
+fn test_chat_response_stream_chunk() {
+    let json = r#"{
+  "model": "llama3.2",
+  "created_at": "2023-08-04T08:52:19.385406455-07:00",
+  "message": { "role": "assistant", "content": "The" },
+  "done": false
+}"#;
+
+    let chunk: ChatResponse = assert_round_trip(json);
+    assert_eq!(chunk.message.content, "The");
+    assert!(!chunk.done);
+    assert!(chunk.eval_count.is_none());
+}
+
+#[test]
+fn test_chat_response_final() {
+    let json = r#"{
+  "model": "llama3.2",
+  "created_at": "2023-12-12T14:13:43.416799Z",
+  "message": {
+    "role": "assistant",
+    "content": "Hello! How are you today?",
+    "thinking": "The user greeted me."
+  },
+  "done": true,
+  "done_reason": "stop",
+  "total_duration": 5191566416,
+  "load_duration": 2154458,
+  "prompt_eval_count": 26,
+  "prompt_eval_duration": 383809000,
+  "eval_count": 298,
+  "eval_duration": 4799921000
+}"#;
+
+    let response: ChatResponse = assert_round_trip(json);
+    assert_eq!(response.done_reason.as_deref(), Some("stop"));
+    assert_eq!(
+        response.message.thinking.as_deref(),
+        Some("The user greeted me.")
+    );
+
+    let stats = response.performance_stats();
+    assert_eq!(stats.prompt_eval_count, Some(26));
+    assert_eq!(stats.eval_count, Some(298));
+    assert!((stats.total_duration_ms.unwrap() - 5191.566416).abs() < 1e-9);
+}
+
+#[test]
+fn test_chat_response_with_tool_calls() {
+    let json = r#"{
+  "model": "llama3.2",
+  "created_at": "2024-07-22T20:33:28.123648Z",
+  "message": {
+    "role": "assistant",
+    "content": "",
+    "tool_calls": [
+      {
+        "function": {
+          "name": "get_current_weather",
+          "arguments": { "format": "celsius", "location": "Paris, FR" }
+        }
+      }
+    ]
+  },
+  "done_reason": "stop",
+  "done": true,
+  "total_duration": 885095291,
+  "load_duration": 3753500,
+  "prompt_eval_count": 122,
+  "prompt_eval_duration": 328493000,
+  "eval_count": 33,
+  "eval_duration": 552222000
+}"#;
+
+    let response: ChatResponse = assert_round_trip(json);
+    assert!(response.message.has_tool_calls());
+    let call = &response.message.tool_calls.as_ref().unwrap()[0];
+    assert_eq!(call.function.name, "get_current_weather");
+    assert_eq!(call.function.arguments["location"], "Paris, FR");
+
+    // 工具结果作为 tool 消息回传
+    let result = ChatMessage::tool("get_current_weather", "22°C, sunny");
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["role"], "tool");
+    assert_eq!(value["tool_name"], "get_current_weather");
+}
@@ -0,0 +1,248 @@
+#![cfg(feature = "client")]
+
+use ollama_models::*;
+use serde_json::json;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_version_and_list_models() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/version"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "version": "0.5.7" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [
+                { "name": "llama3.2:latest", "size": 2019393189, "details": { "family": "llama" } }
+            ]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/ps"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "models": [
+                {
+                    "name": "llama3.2:latest",
+                    "size": 5137025024u64,
+                    "size_vram": 5137025024u64,
+                    "expires_at": "2024-06-04T14:38:31.83753-07:00"
+                }
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    // 末尾的 `/` 会被去掉
+    let client = OllamaClient::new(format!("{}/", server.uri()));
+    assert_eq!(client.version().await.unwrap(), "0.5.7");
+
+    let models = client.list_models().await.unwrap();
+    assert!(models.contains("llama3.2"));
+
+    let running = client.running_models().await.unwrap();
+    assert_eq!(running.models[0].size_vram, 5137025024);
+}
+
+#[tokio::test]
+async fn test_show_and_delete() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/show"))
+        .and(body_json(json!({ "model": "llama3.2" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "modelfile": "FROM llama3.2",
+            "model_info": { "llama.context_length": 131072 },
+            "capabilities": ["completion", "tools"]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/delete"))
+        .and(body_json(json!({ "model": "missing" })))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_json(json!({ "error": "model 'missing' not found" })),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OllamaClient::new(server.uri());
+    let show = client.show("llama3.2").await.unwrap();
+    assert_eq!(show.context_length(), Some(131072));
+
+    let options = GenerateOptions::new().with_num_ctx(16384);
+    let budget = client.token_budget("llama3.2", Some(&options)).await.unwrap();
+    assert_eq!(budget.num_ctx(), 16384);
+
+    match client.delete("missing").await {
+        Err(OllamaError::Api { status, message }) => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "model 'missing' not found");
+        }
+        other => panic!("Expected API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_pull_reports_progress() {
+    let server = MockServer::start().await;
+    let body = concat!(
+        "{\"status\":\"pulling manifest\"}\n",
+        "{\"status\":\"downloading\",\"digest\":\"sha256:2ae6\",\"total\":100,\"completed\":50}\n",
+        "{\"status\":\"downloading\",\"digest\":\"sha256:2ae6\",\"total\":100,\"completed\":100}\n",
+        "{\"status\":\"verifying sha256 digest\"}\n",
+        "{\"status\":\"success\"}\n"
+    );
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .and(body_json(json!({ "model": "llama3.2", "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let client = OllamaClient::new(server.uri());
+    let mut statuses = Vec::new();
+    let mut fractions = Vec::new();
+    client
+        .pull("llama3.2", |progress| {
+            statuses.push(progress.status.clone());
+            fractions.extend(progress.fraction());
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(statuses.len(), 5);
+    assert_eq!(statuses.last().unwrap(), "success");
+    assert_eq!(fractions, vec![0.5, 1.0]);
+}
+
+#[tokio::test]
+async fn test_pull_error_in_stream() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/pull"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "{\"status\":\"pulling manifest\"}\n{\"error\":\"pull model manifest: file does not exist\"}\n",
+        ))
+        .mount(&server)
+        .await;
+
+    let client = OllamaClient::new(server.uri());
+    let err = client.pull("nope", |_| {}).await.unwrap_err();
+    assert!(
+        matches!(err, OllamaError::Stream(StreamError::Server(ref m)) if m.contains("does not exist")),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_connection_refused() {
+    // 没有服务监听的端口
+    let client = OllamaClient::new("http://127.0.0.1:9");
+    assert!(matches!(client.version().await, Err(OllamaError::Http(_))));
+}
+
+#[tokio::test]
+async fn test_embed_batches_in_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(
+            json!({ "model": "all-minilm", "input": ["a", "b"] }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "all-minilm",
+            "embeddings": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(json!({ "model": "all-minilm", "input": ["c"] })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "model": "all-minilm",
+            "embeddings": [[0.0, 0.0, 1.0]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OllamaClient::new(server.uri());
+    let inputs: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let embeddings = client.embed_batch("all-minilm", &inputs, 2).await.unwrap();
+    assert_eq!(
+        embeddings,
+        vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0]
+        ]
+    );
+    assert_eq!(cosine_similarity(&embeddings[0], &embeddings[1]), Some(0.0));
+}
+
+#[tokio::test]
+async fn test_embed_dimension_checks() {
+    let server = MockServer::start().await;
+    // 第一批 3 维，第二批 2 维
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(json!({ "model": "m", "input": ["a"] })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "embeddings": [[0.1, 0.2, 0.3]] })),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(json!({ "model": "m", "input": ["b"] })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "embeddings": [[0.1, 0.2]] })),
+        )
+        .mount(&server)
+        .await;
+    // 请求了 2 条只返回 1 条
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(json!({ "model": "m", "input": ["x", "y"] })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "embeddings": [[0.1, 0.2]] })),
+        )
+        .mount(&server)
+        .await;
+    // 请求的维度与返回的不一致
+    Mock::given(method("POST"))
+        .and(path("/api/embed"))
+        .and(body_json(
+            json!({ "model": "m", "input": "z", "dimensions": 4 }),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "embeddings": [[0.1, 0.2]] })),
+        )
+        .mount(&server)
+        .await;
+
+    let client = OllamaClient::new(server.uri());
+    let inputs = vec!["a".to_string(), "b".to_string()];
+    let err = client.embed_batch("m", &inputs, 1).await.unwrap_err();
+    assert!(
+        matches!(err, OllamaError::InvalidResponse(ref m) if m.contains("dimension 2 but expected 3")),
+        "{}",
+        err
+    );
+
+    let request = EmbedRequest::new("m", vec!["x".to_string(), "y".to_string()]);
+    let err = client.embed(&request).await.unwrap_err();
+    assert!(err.to_string().contains("Expected 2 embeddings"), "{}", err);
+
+    let request = EmbedRequest::new("m", "z").with_dimensions(4);
+    let err = client.embed(&request).await.unwrap_err();
+    assert!(err.to_string().contains("expected 4"), "{}", err);
+}
@@ -0,0 +1,86 @@
+use ollama_models::*;
+use serde_json::{json, Value};
+
+#[test]
+fn test_generate_request_with_options_round_trip() {
+    let json = r#"{
+  "model": "llama3.2",
+  "prompt": "Why is the sky blue?",
+  "stream": false,
+  "system": "You are a physicist.",
+  "template": "{{ .System }} {{ .Prompt }}",
+  "options": {
+    "num_keep": 5,
+    "seed": 42,
+    "num_predict": 100,
+    "top_k": 20,
+    "top_p": 0.9,
+    "temperature": 0.8,
+    "repeat_penalty": 1.2,
+    "num_ctx": 1024,
+    "stop": ["\n", "user:"]
+  },
+  "keep_alive": "10m"
+}"#;
+
+    let request: OllamaRequest = serde_json::from_str(json).unwrap();
+    assert_eq!(request.system.as_deref(), Some("You are a physicist."));
+    let options = request.options.as_ref().unwrap();
+    assert_eq!(options.num_ctx, Some(1024));
+    assert_eq!(options.seed, Some(42));
+    // 未建模的参数原样保留
+    assert_eq!(options.additional["num_keep"], 5);
+    assert!(request.validate().is_ok());
+
+    let original: Value = serde_json::from_str(json).unwrap();
+    assert_eq!(serde_json::to_value(&request).unwrap(), original);
+}
+
+#[test]
+fn test_generate_request_with_schema_format() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "intent": { "type": "string" },
+            "confidence": { "type": "number" }
+        },
+        "required": ["intent", "confidence"]
+    });
+    let request = OllamaRequest::new("qwen2.5", "明早 7 点提醒我开会")
+        .with_format(Format::schema(schema.clone()))
+        .with_options(GenerateOptions::new().with_temperature(0.0))
+        .with_keep_alive(KeepAlive::Seconds(-1));
+
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({
+            "model": "qwen2.5",
+            "prompt": "明早 7 点提醒我开会",
+            "stream": false,
+            "format": schema,
+            "options": { "temperature": 0.0 },
+            "keep_alive": -1
+        })
+    );
+
+    // 旧格式（只有 model / prompt / stream / context）仍然可以解析
+    let legacy: OllamaRequest =
+        serde_json::from_str(r#"{"model":"m","prompt":"p","stream":false,"context":[1,2]}"#)
+            .unwrap();
+    assert_eq!(legacy.context, Some(vec![1, 2]));
+    assert!(legacy.format.is_none() && legacy.options.is_none());
+}
+
+#[test]
+fn test_generate_request_validation() {
+    assert!(OllamaRequest::new("", "p").validate().is_err());
+    assert!(OllamaRequest::new("m", "p")
+        .with_raw(true)
+        .with_system("s")
+        .validate()
+        .is_err());
+    assert!(OllamaRequest::new("m", "p")
+        .with_options(GenerateOptions::new().with_top_p(2.0))
+        .validate()
+        .is_err());
+}
@@ -0,0 +1,75 @@
+//! 检入的 TypeScript 类型定义检查
+//!
+//! 各 crate 用 [ts-rs](https://docs.rs/ts-rs) 生成前端使用的类型定义，并检入在
+//! `gui/frontend/src/types/generated/<crate>/`。各 crate 的 `typescript` 测试调用
+//! [`check_bindings`]，检入的定义与 Rust 类型不一致时测试失败；设置
+//! `UPDATE_TS_BINDINGS` 环境变量运行测试则重新生成：
+//!
+//! ```bash
+//! UPDATE_TS_BINDINGS=1 cargo test -p message-models -p ollama-models -p gui-server typescript
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 重新生成检入定义的环境变量
+pub const UPDATE_ENV: &str = "UPDATE_TS_BINDINGS";
+
+/// 比较 `export` 生成的类型定义与检入在 `checked_in` 目录的定义，不一致时 panic
+///
+/// 设置了 [`UPDATE_ENV`] 时改为清空 `checked_in` 并重新生成；`package` 用于错误提示中的命令
+pub fn check_bindings<E: Debug>(
+    package: &str,
+    checked_in: &Path,
+    export: impl Fn(&Path) -> Result<(), E>,
+) {
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        let _ = fs::remove_dir_all(checked_in);
+        export(checked_in).unwrap();
+        return;
+    }
+
+    let generated_dir = std::env::temp_dir().join(format!("{}-ts-{}", package, std::process::id()));
+    export(&generated_dir).unwrap();
+    let generated = read_bindings(&generated_dir);
+    let _ = fs::remove_dir_all(&generated_dir);
+
+    let existing = read_bindings(checked_in);
+    let hint = format!(
+        "TypeScript bindings are out of date, run `{}=1 cargo test -p {} typescript`",
+        UPDATE_ENV, package
+    );
+    assert_eq!(
+        generated.keys().collect::<Vec<_>>(),
+        existing.keys().collect::<Vec<_>>(),
+        "{}",
+        hint
+    );
+    for (path, content) in &generated {
+        assert_eq!(content, &existing[path], "{}: {}", path.display(), hint);
+    }
+}
+
+/// 读取目录下所有 `.ts` 文件（键为相对路径）
+fn read_bindings(dir: &Path) -> BTreeMap<PathBuf, String> {
+    fn walk(root: &Path, dir: &Path, out: &mut BTreeMap<PathBuf, String>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(root, &path, out);
+            } else if path.extension().is_some_and(|ext| ext == "ts") {
+                let content = fs::read_to_string(&path).unwrap();
+                out.insert(path.strip_prefix(root).unwrap().to_path_buf(), content);
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk(dir, dir, &mut out);
+    out
+}
@@ -1,3 +1,15 @@
+// 与服务端共享的类型由 Rust 生成（types/generated），这里只补充前端附加的字段
+import type { AICoreConfig as AICoreConfigDto } from './generated/gui-server/AICoreConfig'
+import type { AddMessageRequest as AddMessageRequestDto } from './generated/gui-server/AddMessageRequest'
+import type { CheckConnectionRequest } from './generated/gui-server/CheckConnectionRequest'
+import type { MessagePreset as MessagePresetDto } from './generated/gui-server/MessagePreset'
+import type { OllamaConfig as OllamaConfigDto } from './generated/gui-server/OllamaConfig'
+import type { SendSystemPromptRequest } from './generated/gui-server/SendSystemPromptRequest'
+import type { UpdateMessageRequest as UpdateMessageRequestDto } from './generated/gui-server/UpdateMessageRequest'
+import type { OllamaResponse } from './generated/ollama-models/OllamaResponse'
+
+export type { CheckConnectionRequest, OllamaResponse }
+
 // API 响应类型
 export interface ApiResponse<T> {
   success: boolean
@@ -5,34 +17,20 @@ export interface ApiResponse<T> {
   error?: string
 }
 
-// AI-Core 配置类型
-export interface AICoreConfig {
-  id: number
-  name: string
-  url: string
-  description: string
+// AI-Core 配置类型（附加连接状态）
+export interface AICoreConfig extends AICoreConfigDto {
   status?: 'online' | 'offline'
   responseTime?: number
   lastCheck?: string
 }
 
-// Ollama 配置类型
-export interface OllamaConfig {
-  id: number
-  name: string
-  url: string
-  model: string
-  description: string
+// Ollama 配置类型（附加连接状态）
+export interface OllamaConfig extends OllamaConfigDto {
   status?: 'online' | 'offline'
   responseTime?: number
   lastCheck?: string
 }
 
-// 连接检测请求
-export interface CheckConnectionRequest {
-  id: number
-}
-
 // 连接检测响应
 export interface CheckConnectionResponse {
   success: boolean
@@ -41,39 +39,29 @@ export interface CheckConnectionResponse {
   error?: string
 }
 
-// 消息预设类型
-export interface MessagePreset {
-  id: number
-  title: string
-  content: string
-  type: 'system' | 'user' | 'assistant'
+// 消息预设的角色
+export type MessagePresetType = 'system' | 'user' | 'assistant'
+
+// 消息预设类型（收窄 type 与 tags）
+export interface MessagePreset extends Omit<MessagePresetDto, 'type' | 'tags'> {
+  type: MessagePresetType
   tags?: string
-  createdAt: string
-  updatedAt: string
 }
 
 // 添加消息预设请求
-export interface AddMessageRequest {
-  title: string
-  content: string
-  type: 'system' | 'user' | 'assistant'
+export interface AddMessageRequest extends Omit<AddMessageRequestDto, 'type' | 'tags'> {
+  type: MessagePresetType
   tags?: string
 }
 
 // 更新消息预设请求
-export interface UpdateMessageRequest {
-  title: string
-  content: string
-  type: 'system' | 'user' | 'assistant'
+export interface UpdateMessageRequest extends Omit<UpdateMessageRequestDto, 'type' | 'tags'> {
+  type: MessagePresetType
   tags?: string
 }
 
 // 系统参数请求
-export interface SystemPromptRequest {
-  ai_core_id: number
-  system_prompt: string
-  session_id?: string
-}
+export type SystemPromptRequest = SendSystemPromptRequest
 
 // 系统参数响应
 export interface SystemPromptResponse {
@@ -127,33 +115,3 @@ export interface SendMessageResponse {
   session_id: string
   timestamp: string
 }
-
-// Ollama API 响应结构
-export interface OllamaResponse {
-  /// 模型的回答
-  response: string
-  /// 新的会话上下文
-  context?: number[]
-  /// 是否完成
-  done?: boolean
-  /// 完成原因
-  done_reason?: string
-  /// 模型名称
-  model?: string
-  /// 创建时间
-  created_at?: string
-  /// 思考过程（如果模型支持）
-  thinking?: string
-  /// 总处理时间（纳秒）
-  total_duration?: number
-  /// 模型加载时间（纳秒）
-  load_duration?: number
-  /// 提示词评估次数
-  prompt_eval_count?: number
-  /// 提示词评估时间（纳秒）
-  prompt_eval_duration?: number
-  /// 生成评估次数
-  eval_count?: number
-  /// 生成评估时间（纳秒）
-  eval_duration?: number
-}
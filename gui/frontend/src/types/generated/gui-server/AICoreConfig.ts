@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * AI-Core 配置
+ */
+export type AICoreConfig = { id: number, name: string, url: string, description: string, };
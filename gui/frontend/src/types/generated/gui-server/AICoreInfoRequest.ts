@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * AI-Core 信息请求
+ */
+export type AICoreInfoRequest = { url: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 批量检查响应项
+ */
+export type CheckAllItem = { id: number, name: string, url: string, status: string, message: string, response_time: number, timestamp: string, version?: string, model?: string, };
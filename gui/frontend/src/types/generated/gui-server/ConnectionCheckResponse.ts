@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 连接检查响应
+ */
+export type ConnectionCheckResponse = { success: boolean, connected: boolean, status: string, message: string, response_time: number, timestamp: string, version?: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 健康检查响应
+ */
+export type HealthResponse = { status: string, message: string, version: string, };
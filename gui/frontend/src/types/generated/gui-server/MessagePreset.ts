@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 消息预设
+ */
+export type MessagePreset = { id: number, title: string, content: string, type: string, tags: string | null, createdAt: string, updatedAt: string, };
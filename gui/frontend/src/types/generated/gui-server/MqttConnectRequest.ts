@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * MQTT 连接请求
+ */
+export type MqttConnectRequest = { host: string, port: number, subscribe_topic: string, };
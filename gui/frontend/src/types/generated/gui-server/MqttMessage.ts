@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * MQTT 消息
+ */
+export type MqttMessage = { topic: string, payload: string, timestamp: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Ollama 状态检查请求
+ */
+export type OllamaStatusRequest = { url: string, model: string, };
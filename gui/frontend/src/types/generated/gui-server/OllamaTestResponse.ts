@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Ollama 测试响应
+ */
+export type OllamaTestResponse = { success: boolean, connected: boolean, response_time: number, timestamp: string, data?: JsonValue, error?: string, };
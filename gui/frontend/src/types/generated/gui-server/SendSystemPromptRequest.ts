@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 发送系统参数请求
+ */
+export type SendSystemPromptRequest = { ai_core_id: number, system_prompt: string, session_id?: string, };
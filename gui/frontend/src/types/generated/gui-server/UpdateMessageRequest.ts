@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 更新消息预设请求
+ */
+export type UpdateMessageRequest = { title: string, content: string, type: string, tags: string | null, };
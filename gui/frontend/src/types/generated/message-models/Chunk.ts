@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 一个数据块
+ */
+export type Chunk = { 
+/**
+ * 传输 ID，同一负载的所有块共享
+ */
+transfer_id: string, 
+/**
+ * 块序号（从 0 开始）
+ */
+index: number, 
+/**
+ * 块总数
+ */
+count: number, 
+/**
+ * 每块的字节数（最后一块可以更短）
+ */
+chunk_size: number, 
+/**
+ * 完整负载的字节数
+ */
+total_size: number, 
+/**
+ * 完整负载的 SHA-256（十六进制）
+ */
+hash: string, 
+/**
+ * 完整负载的 content-type
+ */
+content_type?: string, 
+/**
+ * 本块数据（base64）
+ */
+data: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 模块调用
+ */
+export type Command = { 
+/**
+ * 意图名称（如 `reminder.create`）
+ */
+intent: string, 
+/**
+ * 目标模块 ID（如 `mod-002`）
+ */
+target: string, 
+/**
+ * 调用参数
+ */
+params: { [key in string]?: JsonValue }, };
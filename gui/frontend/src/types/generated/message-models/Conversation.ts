@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Turn } from "./Turn";
+
+/**
+ * 对话记录
+ */
+export type Conversation = { 
+/**
+ * 会话 ID
+ */
+session_id?: string, 
+/**
+ * 按时间顺序排列的对话轮次
+ */
+turns: Array<Turn>, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MessageContent } from "./MessageContent";
+import type { MessageMeta } from "./MessageMeta";
+import type { MessageType } from "./MessageType";
+
+/**
+ * 消息信封（顶层结构）
+ *
+ * 反序列化时由 `type` 决定 `content` 的变体，类型不匹配时报错；
+ * 需要兼容旧数据时使用 [`Envelope::from_json_lenient`]
+ */
+export type Envelope = { type: MessageType, content: MessageContent, meta: MessageMeta, };
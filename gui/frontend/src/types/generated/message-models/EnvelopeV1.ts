@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MessageContent } from "./MessageContent";
+import type { MessageMetaV1 } from "./MessageMetaV1";
+import type { MessageType } from "./MessageType";
+
+/**
+ * 消息信封（顶层结构）
+ *
+ * 与 v0 相同，反序列化时由 `type` 决定 `content` 的变体
+ */
+export type EnvelopeV1 = { type: MessageType, content: MessageContent, meta: MessageMetaV1, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 错误码
+ *
+ * 序列化为字符串（如 `"E_TIMEOUT"`），未知错误码保存在 `Other` 中
+ */
+export type ErrorCode = string;
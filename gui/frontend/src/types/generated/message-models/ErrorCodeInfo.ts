@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCategory } from "./ErrorCategory";
+
+/**
+ * 错误码的元信息
+ */
+export type ErrorCodeInfo = { category: ErrorCategory, 
+/**
+ * 是否值得重试
+ */
+retryable: boolean, 
+/**
+ * 默认的用户提示文案键（如 `error.timeout`）
+ */
+message_key: string, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventError } from "./EventError";
+import type { EventStatus } from "./EventStatus";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 事件内容
+ */
+export type EventContent = { source: string, status: EventStatus, data?: { [key in string]?: JsonValue }, error?: EventError, 
+/**
+ * 是否为最终事件，未设置时按状态推断（见 [`EventContent::is_terminal`]）
+ */
+final?: boolean, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 错误信息
+ */
+export type EventError = { 
+/**
+ * 错误码（未知错误码原样保留，见 [`ErrorCode`]）
+ */
+code: ErrorCode, message: string, details?: { [key in string]?: JsonValue }, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 模块会发出的事件定义
+ */
+export type EventSpec = { 
+/**
+ * 事件名称（如 `reminder.fired`）
+ */
+name: string, description: string, 
+/**
+ * 事件 `data` 的 JSON Schema
+ */
+data?: JsonValue, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 事件状态
+ */
+export type EventStatus = "ok" | "error" | "progress" | "cancelled";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 意图定义
+ */
+export type IntentSpec = { 
+/**
+ * 意图名称（如 `reminder.create`）
+ */
+name: string, description: string, 
+/**
+ * 参数的 JSON Schema
+ */
+params: JsonValue, };
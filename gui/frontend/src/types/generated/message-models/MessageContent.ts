@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventContent } from "./EventContent";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 消息内容（可以是字符串或事件对象）
+ *
+ * 单独反序列化时按形状猜测变体（宽松模式）；作为 [`Envelope`] 的一部分时，
+ * 由消息类型决定变体，见 [`MessageContent::from_value`]
+ */
+export type MessageContent = string | EventContent | { [key in string]?: JsonValue };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Signature } from "./Signature";
+
+/**
+ * 消息元数据
+ *
+ * 追踪字段的约定：
+ * - `message_id`：消息自身的唯一 ID
+ * - `correlation_id`：一次编排的根消息 ID，同一编排中的所有消息共享
+ * - `causation_id`：直接触发本消息的上一条消息 ID
+ * - `session_id` / `client_id`：所属会话与发起的客户端
+ *
+ * `signature` 为可选的消息签名，见 [`crate::signing`]
+ */
+export type MessageMeta = { schema_version: string, timestamp: string, message_id?: string, correlation_id?: string, causation_id?: string, session_id?: string, client_id?: string, signature?: Signature, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Signature } from "./Signature";
+
+/**
+ * 消息元数据
+ */
+export type MessageMetaV1 = { schema_version: string, 
+/**
+ * 消息唯一 ID（必填）
+ */
+message_id: string, timestamp: string, 
+/**
+ * 关联 ID，同一次编排中的所有消息共享
+ */
+correlation_id?: string, 
+/**
+ * 回复的目标消息 ID（对应 v0 的 `causation_id`）
+ */
+reply_to?: string, 
+/**
+ * 会话 ID
+ */
+session_id?: string, 
+/**
+ * 消息签名，见 [`crate::signing`]
+ */
+signature?: Signature, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EventSpec } from "./EventSpec";
+import type { IntentSpec } from "./IntentSpec";
+
+/**
+ * 模块定义
+ */
+export type ModuleSpec = { 
+/**
+ * 模块 ID（如 `mod-002`），即 command 的 `target`
+ */
+id: string, description: string, intents: Array<IntentSpec>, events: Array<EventSpec>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Command } from "./Command";
+
+/**
+ * 编排器（模型）输出
+ *
+ * 对应系统提示中约定的三种输出：`command` | `reply` | `noop`，
+ * 每次决策必须且只能输出其中一个 JSON 对象
+ */
+export type Output = { "type": "command" } & Command | { "type": "reply", content: string, } | { "type": "noop", content?: string, };
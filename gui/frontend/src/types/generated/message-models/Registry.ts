@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModuleSpec } from "./ModuleSpec";
+
+/**
+ * 已注册模块的集合
+ */
+export type Registry = { modules: Array<ModuleSpec>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 对话中的角色
+ */
+export type Role = "system" | "user" | "event" | "assistant";
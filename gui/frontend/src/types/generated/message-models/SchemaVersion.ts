@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Schema 版本枚举
+ */
+export type SchemaVersion = "v0" | "v1";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Schema 校验违规项
+ */
+export type SchemaViolation = { 
+/**
+ * 违反的 schema 文件（如 `envelope.json`）
+ */
+schema: string, 
+/**
+ * 出错位置（JSON Pointer，如 `/content/status`，根为空字符串）
+ */
+path: string, 
+/**
+ * 错误描述
+ */
+message: string, };
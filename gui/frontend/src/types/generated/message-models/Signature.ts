@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 消息签名
+ */
+export type Signature = { 
+/**
+ * 签名算法（目前只支持 `hmac-sha256`）
+ */
+alg: string, 
+/**
+ * 密钥 ID，即签名模块的 ID
+ */
+key_id: string, 
+/**
+ * 十六进制编码的签名值
+ */
+value: string, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Envelope } from "./Envelope";
+import type { Output } from "./Output";
+import type { Role } from "./Role";
+
+/**
+ * 对话中的一轮：一条输入消息或一次模型输出
+ */
+export type Turn = { role: Role, 
+/**
+ * 输入消息（`role` 为 `system` / `user` / `event` 时）
+ */
+envelope?: Envelope, 
+/**
+ * 模型输出（`role` 为 `assistant` 时）
+ */
+output?: Output, timestamp: string, };
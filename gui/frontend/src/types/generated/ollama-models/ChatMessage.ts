@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChatRole } from "./ChatRole";
+import type { ToolCall } from "./ToolCall";
+
+/**
+ * `/api/chat` 消息
+ */
+export type ChatMessage = { 
+/**
+ * 角色
+ */
+role: ChatRole, 
+/**
+ * 消息内容
+ */
+content: string, 
+/**
+ * 图片（base64 编码，多模态模型使用）
+ */
+images?: Array<string>, 
+/**
+ * 模型发起的工具调用
+ */
+tool_calls?: Array<ToolCall>, 
+/**
+ * 思考过程（如果模型支持）
+ */
+thinking?: string, 
+/**
+ * 工具结果对应的工具名称（`role` 为 `tool` 时使用）
+ */
+tool_name?: string, };
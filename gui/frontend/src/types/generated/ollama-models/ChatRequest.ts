@@ -0,0 +1,43 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChatMessage } from "./ChatMessage";
+import type { Format } from "./Format";
+import type { GenerateOptions } from "./GenerateOptions";
+import type { KeepAlive } from "./KeepAlive";
+import type { Tool } from "./Tool";
+
+/**
+ * `/api/chat` 请求
+ */
+export type ChatRequest = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 对话消息
+ */
+messages: Array<ChatMessage>, 
+/**
+ * 可供模型调用的工具
+ */
+tools?: Array<Tool>, 
+/**
+ * 输出格式
+ */
+format?: Format, 
+/**
+ * 模型参数（temperature、num_ctx 等）
+ */
+options?: GenerateOptions, 
+/**
+ * 是否流式输出（Ollama 默认流式）
+ */
+stream: boolean, 
+/**
+ * 是否输出思考过程（如果模型支持）
+ */
+think?: boolean, 
+/**
+ * 模型保留时间
+ */
+keep_alive?: KeepAlive, };
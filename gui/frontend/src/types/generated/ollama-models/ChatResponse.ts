@@ -0,0 +1,51 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChatMessage } from "./ChatMessage";
+
+/**
+ * `/api/chat` 响应（流式输出时为其中一块）
+ */
+export type ChatResponse = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 模型回复
+ */
+message: ChatMessage, 
+/**
+ * 是否完成
+ */
+done: boolean, 
+/**
+ * 完成原因
+ */
+done_reason?: string, 
+/**
+ * 总处理时间（纳秒）
+ */
+total_duration?: number, 
+/**
+ * 模型加载时间（纳秒）
+ */
+load_duration?: number, 
+/**
+ * 提示词评估次数
+ */
+prompt_eval_count?: number, 
+/**
+ * 提示词评估时间（纳秒）
+ */
+prompt_eval_duration?: number, 
+/**
+ * 生成评估次数
+ */
+eval_count?: number, 
+/**
+ * 生成评估时间（纳秒）
+ */
+eval_duration?: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `/api/chat` 消息角色
+ */
+export type ChatRole = "system" | "user" | "assistant" | "tool";
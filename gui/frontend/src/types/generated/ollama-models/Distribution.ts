@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 一组样本的分布
+ */
+export type Distribution = { 
+/**
+ * 样本数
+ */
+count: number, mean: number, min: number, max: number, p50: number, p95: number, p99: number, };
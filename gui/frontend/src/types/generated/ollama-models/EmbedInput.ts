@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 需要生成向量的文本：单条或多条
+ */
+export type EmbedInput = string | Array<string>;
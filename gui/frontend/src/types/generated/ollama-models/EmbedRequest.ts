@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EmbedInput } from "./EmbedInput";
+import type { GenerateOptions } from "./GenerateOptions";
+import type { KeepAlive } from "./KeepAlive";
+
+/**
+ * `/api/embed` 请求
+ */
+export type EmbedRequest = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 文本
+ */
+input: EmbedInput, 
+/**
+ * 超出上下文长度时是否截断（为 false 时返回错误）
+ */
+truncate?: boolean, 
+/**
+ * 输出向量的维度（模型支持时）
+ */
+dimensions?: number, 
+/**
+ * 模型参数
+ */
+options?: GenerateOptions, 
+/**
+ * 模型保留时间
+ */
+keep_alive?: KeepAlive, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `/api/embed` 响应
+ */
+export type EmbedResponse = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 向量，与输入顺序一致
+ */
+embeddings: Array<Array<number>>, 
+/**
+ * 总处理时间（纳秒）
+ */
+total_duration?: number, 
+/**
+ * 模型加载时间（纳秒）
+ */
+load_duration?: number, 
+/**
+ * 输入的 token 数
+ */
+prompt_eval_count?: number, };
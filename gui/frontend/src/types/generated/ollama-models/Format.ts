@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 输出格式：`"json"` 或 JSON Schema（结构化输出）
+ */
+export type Format = "json" | Record<string, unknown>;
@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 模型参数（`/api/generate` 与 `/api/chat` 的 `options`）
+ *
+ * 常用参数有类型，其余参数（如 `mirostat`、`num_gpu`）放在 `additional` 中原样传递。
+ */
+export type GenerateOptions = { 
+/**
+ * 采样温度，越高越随机
+ */
+temperature?: number, 
+/**
+ * 核采样概率阈值（0 到 1）
+ */
+top_p?: number, 
+/**
+ * 只从概率最高的 k 个 token 中采样
+ */
+top_k?: number, 
+/**
+ * 上下文窗口大小（token 数）
+ */
+num_ctx?: number, 
+/**
+ * 最多生成的 token 数（`-1` 不限制）
+ */
+num_predict?: number, 
+/**
+ * 随机种子，固定后相同输入得到相同输出
+ */
+seed?: number, 
+/**
+ * 停止词
+ */
+stop?: Array<string>, 
+/**
+ * 重复惩罚
+ */
+repeat_penalty?: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 模型在内存中保留的时间：时长字符串（如 `"5m"`）或秒数（`0` 立即卸载，负数一直保留）
+ */
+export type KeepAlive = string | number;
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 模型详情（`/api/tags`、`/api/ps`、`/api/show` 共用）
+ */
+export type ModelDetails = { 
+/**
+ * 基础模型
+ */
+parent_model: string, 
+/**
+ * 文件格式（如 `gguf`）
+ */
+format: string, 
+/**
+ * 模型家族（如 `llama`）
+ */
+family: string, 
+/**
+ * 包含的模型家族
+ */
+families: Array<string> | null, 
+/**
+ * 参数规模（如 `8.0B`）
+ */
+parameter_size: string, 
+/**
+ * 量化等级（如 `Q4_K_M`）
+ */
+quantization_level: string, };
@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelDetails } from "./ModelDetails";
+
+/**
+ * 已安装的模型（`/api/tags`）
+ */
+export type ModelInfo = { 
+/**
+ * 模型名称（如 `llama3.2:latest`）
+ */
+name: string, 
+/**
+ * 模型标识
+ */
+model: string, 
+/**
+ * 修改时间
+ */
+modified_at: string, 
+/**
+ * 文件大小（字节）
+ */
+size: number, 
+/**
+ * 摘要
+ */
+digest: string, 
+/**
+ * 详情
+ */
+details: ModelDetails, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelInfo } from "./ModelInfo";
+
+/**
+ * `/api/tags` 响应
+ */
+export type ModelList = { 
+/**
+ * 已安装的模型
+ */
+models: Array<ModelInfo>, };
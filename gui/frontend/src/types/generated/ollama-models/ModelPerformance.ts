@@ -0,0 +1,43 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Distribution } from "./Distribution";
+
+/**
+ * 单个模型的汇总性能
+ */
+export type ModelPerformance = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 请求数（全部请求）
+ */
+count: number, 
+/**
+ * 总处理时间（毫秒，最近的样本）
+ */
+total_duration_ms: Distribution | null, 
+/**
+ * 模型加载时间（毫秒，最近的样本）
+ */
+load_duration_ms: Distribution | null, 
+/**
+ * 最近每个请求的生成速度（tokens/s）
+ */
+tokens_per_second: Distribution | null, 
+/**
+ * 提示词 token 总数
+ */
+prompt_tokens: number, 
+/**
+ * 生成 token 总数
+ */
+eval_tokens: number, 
+/**
+ * 整体生成速度：生成 token 总数 / 生成总时间（tokens/s）
+ */
+throughput: number | null, 
+/**
+ * 整体提示词评估速度（tokens/s）
+ */
+prompt_throughput: number | null, };
@@ -0,0 +1,49 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Format } from "./Format";
+import type { GenerateOptions } from "./GenerateOptions";
+import type { KeepAlive } from "./KeepAlive";
+
+/**
+ * Ollama API 请求结构
+ */
+export type OllamaRequest = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 提示词
+ */
+prompt: string, 
+/**
+ * 是否流式输出
+ */
+stream: boolean, 
+/**
+ * 会话上下文（用于保持对话连续性）
+ */
+context?: Array<number>, 
+/**
+ * 系统提示词（覆盖模型自带的）
+ */
+system?: string, 
+/**
+ * 提示词模板（覆盖模型自带的）
+ */
+template?: string, 
+/**
+ * 是否跳过模板，直接把 `prompt` 交给模型
+ */
+raw?: boolean, 
+/**
+ * 输出格式（`"json"` 或 JSON Schema）
+ */
+format?: Format, 
+/**
+ * 模型参数
+ */
+options?: GenerateOptions, 
+/**
+ * 模型保留时间
+ */
+keep_alive?: KeepAlive, };
@@ -0,0 +1,58 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Ollama API 响应结构
+ */
+export type OllamaResponse = { 
+/**
+ * 模型的回答
+ */
+response: string, 
+/**
+ * 新的会话上下文
+ */
+context: Array<number> | null, 
+/**
+ * 是否完成
+ */
+done: boolean, 
+/**
+ * 完成原因
+ */
+done_reason: string | null, 
+/**
+ * 模型名称
+ */
+model: string | null, 
+/**
+ * 创建时间
+ */
+created_at: string | null, 
+/**
+ * 思考过程（如果模型支持）
+ */
+thinking: string | null, 
+/**
+ * 总处理时间（纳秒）
+ */
+total_duration: number | null, 
+/**
+ * 模型加载时间（纳秒）
+ */
+load_duration: number | null, 
+/**
+ * 提示词评估次数
+ */
+prompt_eval_count: number | null, 
+/**
+ * 提示词评估时间（纳秒）
+ */
+prompt_eval_duration: number | null, 
+/**
+ * 生成评估次数
+ */
+eval_count: number | null, 
+/**
+ * 生成评估时间（纳秒）
+ */
+eval_duration: number | null, };
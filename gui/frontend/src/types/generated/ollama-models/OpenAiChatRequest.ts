@@ -0,0 +1,50 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiMessage } from "./OpenAiMessage";
+import type { OpenAiResponseFormat } from "./OpenAiResponseFormat";
+import type { OpenAiStop } from "./OpenAiStop";
+import type { Tool } from "./Tool";
+
+/**
+ * `/v1/chat/completions` 请求
+ */
+export type OpenAiChatRequest = { 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 对话消息
+ */
+messages: Array<OpenAiMessage>, 
+/**
+ * 可供模型调用的工具（与 Ollama 格式相同）
+ */
+tools?: Array<Tool>, 
+/**
+ * 输出格式
+ */
+response_format?: OpenAiResponseFormat, 
+/**
+ * 采样温度
+ */
+temperature?: number, 
+/**
+ * 核采样阈值
+ */
+top_p?: number, 
+/**
+ * 最多生成的 token 数
+ */
+max_tokens?: number, 
+/**
+ * 随机种子
+ */
+seed?: number, 
+/**
+ * 停止词
+ */
+stop?: OpenAiStop, 
+/**
+ * 是否流式输出（默认否）
+ */
+stream?: boolean, };
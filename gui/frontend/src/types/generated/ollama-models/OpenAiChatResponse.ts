@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiChoice } from "./OpenAiChoice";
+import type { OpenAiUsage } from "./OpenAiUsage";
+
+/**
+ * `/v1/chat/completions` 响应
+ */
+export type OpenAiChatResponse = { 
+/**
+ * 响应 id
+ */
+id: string, 
+/**
+ * 固定为 `chat.completion`
+ */
+object: string, 
+/**
+ * 创建时间（Unix 秒）
+ */
+created: number, 
+/**
+ * 模型名称
+ */
+model: string, 
+/**
+ * 候选回复
+ */
+choices: Array<OpenAiChoice>, 
+/**
+ * token 用量
+ */
+usage?: OpenAiUsage, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiMessage } from "./OpenAiMessage";
+
+/**
+ * 候选回复
+ */
+export type OpenAiChoice = { 
+/**
+ * 序号
+ */
+index: number, 
+/**
+ * 回复消息
+ */
+message: OpenAiMessage, 
+/**
+ * 结束原因（`stop`、`length`、`tool_calls`）
+ */
+finish_reason: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiContentPart } from "./OpenAiContentPart";
+
+/**
+ * 消息内容：纯文本或多段内容
+ */
+export type OpenAiContent = string | Array<OpenAiContentPart>;
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiImageUrl } from "./OpenAiImageUrl";
+
+/**
+ * 多段内容中的一段
+ */
+export type OpenAiContentPart = { "type": "text", text: string, } | { "type": "image_url", image_url: OpenAiImageUrl, };
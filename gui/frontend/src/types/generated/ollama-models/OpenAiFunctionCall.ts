@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * OpenAI 工具调用的函数与参数
+ */
+export type OpenAiFunctionCall = { 
+/**
+ * 函数名称
+ */
+name: string, 
+/**
+ * JSON 编码的调用参数
+ */
+arguments: string, };
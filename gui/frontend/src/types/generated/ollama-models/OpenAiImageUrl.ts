@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 图片地址（Ollama 只支持 `data:` URL）
+ */
+export type OpenAiImageUrl = { url: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 结构化输出的 JSON Schema
+ */
+export type OpenAiJsonSchema = { 
+/**
+ * 名称
+ */
+name: string, 
+/**
+ * JSON Schema
+ */
+schema: JsonValue, 
+/**
+ * 是否严格遵守
+ */
+strict?: boolean, };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiContent } from "./OpenAiContent";
+import type { OpenAiRole } from "./OpenAiRole";
+import type { OpenAiToolCall } from "./OpenAiToolCall";
+
+/**
+ * OpenAI 消息
+ */
+export type OpenAiMessage = { 
+/**
+ * 角色
+ */
+role: OpenAiRole, 
+/**
+ * 内容（只有工具调用时可以为空）
+ */
+content: OpenAiContent | null, 
+/**
+ * 思考过程（vLLM、llama.cpp 等的扩展字段）
+ */
+reasoning_content?: string, 
+/**
+ * 模型发起的工具调用
+ */
+tool_calls?: Array<OpenAiToolCall>, 
+/**
+ * 工具结果对应的调用 id
+ */
+tool_call_id?: string, 
+/**
+ * 工具结果对应的函数名称
+ */
+name?: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiJsonSchema } from "./OpenAiJsonSchema";
+
+/**
+ * 输出格式
+ */
+export type OpenAiResponseFormat = { "type": "text" } | { "type": "json_object" } | { "type": "json_schema", json_schema: OpenAiJsonSchema, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * OpenAI 消息角色（`developer` 等同于 `system`）
+ */
+export type OpenAiRole = "system" | "developer" | "user" | "assistant" | "tool";
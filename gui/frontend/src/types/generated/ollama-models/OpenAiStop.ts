@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 停止词：单个或多个
+ */
+export type OpenAiStop = string | Array<string>;
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OpenAiFunctionCall } from "./OpenAiFunctionCall";
+
+/**
+ * OpenAI 工具调用
+ */
+export type OpenAiToolCall = { 
+/**
+ * 调用 id，工具结果通过它关联
+ */
+id: string, 
+/**
+ * 固定为 `function`
+ */
+type: string, 
+/**
+ * 调用的函数
+ */
+function: OpenAiFunctionCall, };
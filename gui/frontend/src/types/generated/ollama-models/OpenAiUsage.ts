@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * token 用量
+ */
+export type OpenAiUsage = { prompt_tokens: number, completion_tokens: number, total_tokens: number, };
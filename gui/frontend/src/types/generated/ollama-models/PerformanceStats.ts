@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 性能统计信息
+ */
+export type PerformanceStats = { total_duration_ms: number | null, load_duration_ms: number | null, prompt_eval_duration_ms: number | null, eval_duration_ms: number | null, prompt_eval_count: number | null, eval_count: number | null, avg_eval_time_per_token_ms: number | null, 
+/**
+ * 生成速度（tokens/s）
+ */
+tokens_per_second: number | null, 
+/**
+ * 提示词评估速度（tokens/s）
+ */
+prompt_tokens_per_second: number | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `/api/pull` 进度（流式返回的每一块）
+ */
+export type PullProgress = { 
+/**
+ * 状态（如 `pulling manifest`、`downloading`、`success`）
+ */
+status: string, 
+/**
+ * 正在下载的层
+ */
+digest?: string, 
+/**
+ * 总字节数
+ */
+total?: number, 
+/**
+ * 已下载字节数
+ */
+completed?: number, };
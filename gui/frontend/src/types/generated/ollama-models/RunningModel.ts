@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelDetails } from "./ModelDetails";
+
+/**
+ * 已加载到内存的模型（`/api/ps`）
+ */
+export type RunningModel = { 
+/**
+ * 模型名称
+ */
+name: string, 
+/**
+ * 模型标识
+ */
+model: string, 
+/**
+ * 占用的内存（字节）
+ */
+size: number, 
+/**
+ * 摘要
+ */
+digest: string, 
+/**
+ * 详情
+ */
+details: ModelDetails, 
+/**
+ * 卸载时间
+ */
+expires_at: string, 
+/**
+ * 占用的显存（字节）
+ */
+size_vram: number, 
+/**
+ * 加载时使用的上下文窗口大小
+ */
+context_length?: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RunningModel } from "./RunningModel";
+
+/**
+ * `/api/ps` 响应
+ */
+export type RunningModelList = { 
+/**
+ * 已加载的模型
+ */
+models: Array<RunningModel>, };
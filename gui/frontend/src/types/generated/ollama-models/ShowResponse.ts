@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ModelDetails } from "./ModelDetails";
+
+/**
+ * `/api/show` 响应
+ */
+export type ShowResponse = { 
+/**
+ * Modelfile 内容
+ */
+modelfile: string, 
+/**
+ * 模型参数（Modelfile 中的 `PARAMETER`）
+ */
+parameters: string, 
+/**
+ * 提示词模板
+ */
+template: string, 
+/**
+ * 许可证
+ */
+license?: string, 
+/**
+ * 详情
+ */
+details: ModelDetails, 
+/**
+ * 模型元数据（如 `llama.context_length`）
+ */
+model_info: { [key in string]?: JsonValue }, 
+/**
+ * 模型能力（如 `completion`、`tools`、`vision`）
+ */
+capabilities: Array<string>, 
+/**
+ * 修改时间
+ */
+modified_at?: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ToolFunction } from "./ToolFunction";
+import type { ToolType } from "./ToolType";
+
+/**
+ * 工具定义
+ */
+export type Tool = { 
+/**
+ * 工具类型
+ */
+type: ToolType, 
+/**
+ * 函数定义
+ */
+function: ToolFunction, };
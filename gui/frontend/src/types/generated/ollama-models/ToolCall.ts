@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ToolCallFunction } from "./ToolCallFunction";
+
+/**
+ * 模型发起的工具调用
+ */
+export type ToolCall = { 
+/**
+ * 调用的函数
+ */
+function: ToolCallFunction, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 工具调用的函数与参数
+ */
+export type ToolCallFunction = { 
+/**
+ * 函数名称
+ */
+name: string, 
+/**
+ * 调用参数
+ */
+arguments: { [key in string]?: JsonValue }, 
+/**
+ * 同一条消息中的调用序号
+ */
+index?: number, };
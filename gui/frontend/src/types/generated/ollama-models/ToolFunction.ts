@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * 函数定义
+ */
+export type ToolFunction = { 
+/**
+ * 函数名称
+ */
+name: string, 
+/**
+ * 函数说明
+ */
+description: string, 
+/**
+ * 参数的 JSON Schema
+ */
+parameters: JsonValue, };
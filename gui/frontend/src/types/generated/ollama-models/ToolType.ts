@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 工具类型（目前 Ollama 只支持函数）
+ */
+export type ToolType = "function";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `/api/version` 响应
+ */
+export type VersionResponse = { 
+/**
+ * Ollama 版本
+ */
+version: string, };
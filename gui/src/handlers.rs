@@ -1,5 +1,6 @@
 use crate::{models::*, AppState};
 use actix_web::{delete, get, post, put, web, HttpResponse, Responder};
+use ollama_models::{OllamaClient, OllamaError};
 use std::time::Instant;
 
 // ==================== AI-Core APIs ====================
@@ -357,48 +358,42 @@ pub async fn delete_ollama_config(
     }
 }
 
+/// 轻量检查 Ollama：读取版本与已安装的模型，返回 (版本, 模型是否已安装)
+async fn probe_ollama(url: &str, model: &str) -> Result<(String, bool), OllamaError> {
+    let client = OllamaClient::new(url);
+    let version = client.version().await?;
+    let models = client.list_models().await?;
+    Ok((version, models.contains(model)))
+}
+
 /// 检查 Ollama 服务状态
 #[post("/api/ollama-status")]
 pub async fn check_ollama_status(request: web::Json<OllamaStatusRequest>) -> impl Responder {
     let start_time = Instant::now();
-    let url = format!("{}/api/generate", request.url);
-
-    let client = reqwest::Client::new();
-    let body = serde_json::json!({
-        "model": request.model,
-        "prompt": "你好",
-        "stream": false
-    });
 
     match tokio::time::timeout(
-        std::time::Duration::from_secs(120), // 2分钟超时
-        client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send(),
+        std::time::Duration::from_secs(10),
+        probe_ollama(&request.url, &request.model),
     )
     .await
     {
-        Ok(Ok(response)) => {
+        Ok(Ok((version, model_installed))) => {
             let response_time = start_time.elapsed().as_millis();
-            if let Ok(data) = response.json::<serde_json::Value>().await {
-                HttpResponse::Ok().json(serde_json::json!({
-                    "success": true,
-                    "connected": true,
-                    "data": data,
-                    "responseTime": response_time,
-                    "timestamp": chrono::Utc::now().to_rfc3339()
-                }))
-            } else {
-                HttpResponse::Ok().json(serde_json::json!({
-                    "success": true,
-                    "connected": false,
-                    "error": "Invalid response",
-                    "responseTime": response_time,
-                    "timestamp": chrono::Utc::now().to_rfc3339()
-                }))
+            let mut body = serde_json::json!({
+                "success": true,
+                "connected": true,
+                "data": {
+                    "version": version,
+                    "model": request.model,
+                    "modelInstalled": model_installed
+                },
+                "responseTime": response_time,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            });
+            if !model_installed {
+                body["error"] = format!("Model '{}' is not installed", request.model).into();
             }
+            HttpResponse::Ok().json(body)
         }
         Ok(Err(e)) => {
             let response_time = start_time.elapsed().as_millis();
@@ -499,90 +494,43 @@ pub async fn test_ollama(request: web::Json<OllamaTestRequest>) -> impl Responde
 #[get("/api/ollama-check-all")]
 pub async fn check_all_ollama(state: web::Data<AppState>) -> impl Responder {
     let ollama_configs = state.ollama_configs.read().await;
-    let client = reqwest::Client::new();
 
     let mut tasks = Vec::new();
 
     for config in ollama_configs.iter() {
         let config = config.clone();
-        let client = client.clone();
 
         let task = tokio::spawn(async move {
             let start_time = Instant::now();
-            let url = format!("{}/api/generate", config.url);
 
-            let body = serde_json::json!({
-                "model": config.model,
-                "prompt": "你好",
-                "stream": false
-            });
-
-            match tokio::time::timeout(
+            let result = tokio::time::timeout(
                 std::time::Duration::from_secs(5),
-                client
-                    .post(&url)
-                    .header("Content-Type", "application/json")
-                    .json(&body)
-                    .send(),
+                probe_ollama(&config.url, &config.model),
             )
-            .await
-            {
-                Ok(Ok(response)) => {
-                    let response_time = start_time.elapsed().as_millis();
-                    if let Ok(_data) = response.json::<serde_json::Value>().await {
-                        CheckAllItem {
-                            id: config.id,
-                            name: config.name,
-                            url: config.url,
-                            status: "online".to_string(),
-                            message: "Connected".to_string(),
-                            response_time,
-                            timestamp: chrono::Utc::now().to_rfc3339(),
-                            version: None,
-                            model: Some(config.model),
-                        }
-                    } else {
-                        CheckAllItem {
-                            id: config.id,
-                            name: config.name,
-                            url: config.url,
-                            status: "offline".to_string(),
-                            message: "Invalid response".to_string(),
-                            response_time,
-                            timestamp: chrono::Utc::now().to_rfc3339(),
-                            version: None,
-                            model: Some(config.model),
-                        }
-                    }
-                }
-                Ok(Err(e)) => {
-                    let response_time = start_time.elapsed().as_millis();
-                    CheckAllItem {
-                        id: config.id,
-                        name: config.name,
-                        url: config.url,
-                        status: "offline".to_string(),
-                        message: e.to_string(),
-                        response_time,
-                        timestamp: chrono::Utc::now().to_rfc3339(),
-                        version: None,
-                        model: Some(config.model),
-                    }
-                }
-                Err(_) => {
-                    let response_time = start_time.elapsed().as_millis();
-                    CheckAllItem {
-                        id: config.id,
-                        name: config.name,
-                        url: config.url,
-                        status: "offline".to_string(),
-                        message: "Request timeout".to_string(),
-                        response_time,
-                        timestamp: chrono::Utc::now().to_rfc3339(),
-                        version: None,
-                        model: Some(config.model),
-                    }
-                }
+            .await;
+            let response_time = start_time.elapsed().as_millis();
+
+            let (status, message, version) = match result {
+                Ok(Ok((version, true))) => ("online", "Connected".to_string(), Some(version)),
+                Ok(Ok((version, false))) => (
+                    "online",
+                    format!("Model '{}' is not installed", config.model),
+                    Some(version),
+                ),
+                Ok(Err(e)) => ("offline", e.to_string(), None),
+                Err(_) => ("offline", "Request timeout".to_string(), None),
+            };
+
+            CheckAllItem {
+                id: config.id,
+                name: config.name,
+                url: config.url,
+                status: status.to_string(),
+                message,
+                response_time,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                version,
+                model: Some(config.model),
             }
         });
 
@@ -716,7 +664,7 @@ pub async fn mqtt_connect(
     state: web::Data<AppState>,
     request: web::Json<MqttConnectRequest>,
 ) -> impl Responder {
-    use mqtt_client::{ClientConfig, MqttClient, QoS};
+    use mqtt_client::{ClientConfig, MqttClient, QoS, ReconnectPolicy};
     use tokio::sync::mpsc;
     
     let req = request.into_inner();
@@ -736,6 +684,7 @@ pub async fn mqtt_connect(
         password: None,
         clean_session: true,
         keep_alive: 60,
+        reconnect: ReconnectPolicy::default(),
     };
     
     let mut mqtt_client = MqttClient::new(config, tx);
@@ -751,17 +700,15 @@ pub async fn mqtt_connect(
     
     log::info!("✅ MQTT 连接成功");
     
-    // 订阅主题
-    if let Some(client) = mqtt_client.client.as_ref() {
-        if let Err(e) = client.subscribe(&req.subscribe_topic, QoS::AtLeastOnce).await {
-            log::error!("❌ MQTT 订阅失败: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "success": false,
-                "error": format!("订阅失败: {}", e)
-            }));
-        }
-        log::info!("✅ 成功订阅主题: {}", req.subscribe_topic);
+    // 订阅主题（断线重连后会自动重新订阅）
+    if let Err(e) = mqtt_client.subscribe(&req.subscribe_topic, QoS::AtLeastOnce).await {
+        log::error!("❌ MQTT 订阅失败: {}", e);
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": format!("订阅失败: {}", e)
+        }));
     }
+    log::info!("✅ 成功订阅主题: {}", req.subscribe_topic);
     
     // 保存客户端
     let mut mqtt_client_guard = state.mqtt_client.write().await;
@@ -8,6 +8,8 @@ use tokio::sync::RwLock;
 
 mod handlers;
 mod models;
+#[cfg(test)]
+mod typescript;
 
 use models::*;
 
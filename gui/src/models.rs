@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 /// AI-Core 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct AICoreConfig {
     pub id: i32,
     pub name: String,
@@ -10,7 +11,7 @@ pub struct AICoreConfig {
 }
 
 /// Ollama 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct OllamaConfig {
     pub id: i32,
     pub name: String,
@@ -20,7 +21,7 @@ pub struct OllamaConfig {
 }
 
 /// 消息预设
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct MessagePreset {
     pub id: i32,
     pub title: String,
@@ -34,7 +35,7 @@ pub struct MessagePreset {
 }
 
 /// 添加消息预设请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct AddMessageRequest {
     pub title: String,
     pub content: String,
@@ -43,7 +44,7 @@ pub struct AddMessageRequest {
 }
 
 /// 更新消息预设请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct UpdateMessageRequest {
     pub title: String,
     pub content: String,
@@ -52,33 +53,33 @@ pub struct UpdateMessageRequest {
 }
 
 /// 连接检查请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct CheckConnectionRequest {
     pub id: i32,
 }
 
 /// Ollama 状态检查请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct OllamaStatusRequest {
     pub url: String,
     pub model: String,
 }
 
 /// Ollama 测试请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct OllamaTestRequest {
     pub url: String,
     pub prompt: Option<String>,
 }
 
 /// AI-Core 信息请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct AICoreInfoRequest {
     pub url: String,
 }
 
 /// 健康检查响应
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct HealthResponse {
     pub status: String,
     pub message: String,
@@ -86,49 +87,57 @@ pub struct HealthResponse {
 }
 
 /// 连接检查响应
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 pub struct ConnectionCheckResponse {
     pub success: bool,
     pub connected: bool,
     pub status: String,
     pub message: String,
+    #[ts(type = "number")]
     pub response_time: u128,
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub version: Option<String>,
 }
 
 /// 批量检查响应项
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 pub struct CheckAllItem {
     pub id: i32,
     pub name: String,
     pub url: String,
     pub status: String,
     pub message: String,
+    #[ts(type = "number")]
     pub response_time: u128,
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub version: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub model: Option<String>,
 }
 
 /// Ollama 测试响应
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 pub struct OllamaTestResponse {
     pub success: bool,
     pub connected: bool,
+    #[ts(type = "number")]
     pub response_time: u128,
     pub timestamp: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub data: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub error: Option<String>,
 }
 
 /// MQTT 连接请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct MqttConnectRequest {
     pub host: String,
     pub port: u16,
@@ -136,14 +145,14 @@ pub struct MqttConnectRequest {
 }
 
 /// MQTT 发布请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct MqttPublishRequest {
     pub topic: String,
     pub payload: String,
 }
 
 /// MQTT 消息
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
 pub struct MqttMessage {
     pub topic: String,
     pub payload: String,
@@ -151,11 +160,12 @@ pub struct MqttMessage {
 }
 
 /// 发送系统参数请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
 pub struct SendSystemPromptRequest {
     pub ai_core_id: i32,
     pub system_prompt: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
     pub session_id: Option<String>,
 }
 
@@ -0,0 +1,45 @@
+//! 前端 API 类型定义导出
+//!
+//! `gui/frontend` 使用的请求/响应类型由 [ts-rs](https://docs.rs/ts-rs) 从 [`crate::models`]
+//! 生成，检入在 [`BINDINGS_DIR`]。修改这些类型后需要重新生成：
+//!
+//! ```bash
+//! UPDATE_TS_BINDINGS=1 cargo test -p gui-server typescript
+//! ```
+
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+use crate::models::*;
+
+/// 检入的类型定义目录（相对于 crate 根目录）
+const BINDINGS_DIR: &str = "frontend/src/types/generated/gui-server";
+
+/// 将所有 API 类型导出到 `out_dir`，每个类型一个 `.ts` 文件
+fn export_bindings(out_dir: impl AsRef<Path>) -> Result<(), ExportError> {
+    let out_dir = out_dir.as_ref();
+    AICoreConfig::export_all_to(out_dir)?;
+    OllamaConfig::export_all_to(out_dir)?;
+    MessagePreset::export_all_to(out_dir)?;
+    AddMessageRequest::export_all_to(out_dir)?;
+    UpdateMessageRequest::export_all_to(out_dir)?;
+    CheckConnectionRequest::export_all_to(out_dir)?;
+    OllamaStatusRequest::export_all_to(out_dir)?;
+    OllamaTestRequest::export_all_to(out_dir)?;
+    AICoreInfoRequest::export_all_to(out_dir)?;
+    HealthResponse::export_all_to(out_dir)?;
+    ConnectionCheckResponse::export_all_to(out_dir)?;
+    CheckAllItem::export_all_to(out_dir)?;
+    OllamaTestResponse::export_all_to(out_dir)?;
+    MqttConnectRequest::export_all_to(out_dir)?;
+    MqttPublishRequest::export_all_to(out_dir)?;
+    MqttMessage::export_all_to(out_dir)?;
+    SendSystemPromptRequest::export_all_to(out_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_typescript_bindings_up_to_date() {
+    let checked_in = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_DIR);
+    ts_bindings::check_bindings("gui-server", &checked_in, |dir| export_bindings(dir));
+}